@@ -1,12 +1,44 @@
 use std::env;
 use std::fs;
+use std::io::Read;
 use std::path::PathBuf;
+use std::time::Duration;
 
 // Use the unified download logic from manager.rs
 // Since build.rs can't directly use the manager module, we'll implement a simplified version
 // that uses the same logic patterns but with build-time compatible dependencies
 
+/// Set to skip network downloads (e.g. in CI that supplies prebuilt resources)
+const SKIP_DOWNLOAD_ENV: &str = "PANDOC_DESKTOP_SKIP_DOWNLOAD";
+
+/// Optional lock file pinning tool versions and archive hashes, e.g.
+/// { "pandoc": { "version": "3.7.0.2", "sha256": { "linux-x86_64": "<hex>" } } }
+/// PANDOC_VERSION / PANDOC_SHA256 (and TYPST_*) env vars take precedence
+const TOOLS_LOCK_FILE: &str = "tools.lock";
+
+/// Print a download progress line every this many bytes
+const PROGRESS_STEP: usize = 10 * 1024 * 1024;
+
+/// When set, releases are fetched from the GitHub API with this token instead of UNGH,
+/// which raises the unauthenticated rate limit of 60 requests per hour
+const GITHUB_TOKEN_ENV: &str = "GITHUB_TOKEN";
+
+/// Attempts for each release API request before giving up
+const API_ATTEMPTS: u32 = 3;
+
 fn main() {
+    println!("cargo:rerun-if-env-changed={}", SKIP_DOWNLOAD_ENV);
+    println!("cargo:rerun-if-env-changed=PANDOC_BIN");
+    println!("cargo:rerun-if-env-changed=TYPST_BIN");
+    println!("cargo:rerun-if-env-changed={}", GITHUB_TOKEN_ENV);
+    for tool in ["PANDOC", "TYPST"] {
+        println!("cargo:rerun-if-env-changed={}_VERSION", tool);
+        println!("cargo:rerun-if-env-changed={}_SHA256", tool);
+    }
+    if PathBuf::from(TOOLS_LOCK_FILE).exists() {
+        println!("cargo:rerun-if-changed={}", TOOLS_LOCK_FILE);
+    }
+
     // Download and prepare Pandoc as a resource
     if let Err(e) = prepare_tool_resource("pandoc") {
         println!("cargo:warning=Failed to prepare Pandoc resource: {}", e);
@@ -53,18 +85,79 @@ fn prepare_tool_resource(tool: &str) -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
+    // A pre-supplied binary (PANDOC_BIN / TYPST_BIN) replaces the download entirely
+    let bin_env = format!("{}_BIN", tool.to_uppercase());
+    if let Ok(bin_path) = env::var(&bin_env) {
+        let target = tool_dir.join(&exe_name);
+        copy_prebuilt_binary(&PathBuf::from(&bin_path), &target)?;
+        println!(
+            "cargo:warning=Copied {} from {} ({}) to {}",
+            tool,
+            bin_path,
+            bin_env,
+            target.display()
+        );
+        return Ok(());
+    }
+
+    if skip_download() {
+        println!(
+            "cargo:warning={} is set, skipping {} download (expected in {})",
+            SKIP_DOWNLOAD_ENV,
+            tool,
+            tool_dir.display()
+        );
+        return Ok(());
+    }
+
     println!(
         "cargo:warning=Downloading {} for {}-{}...",
         tool, target_os, target_arch
     );
 
-    // Get the latest release URL for this platform
-    let download_url = get_tool_download_url(tool, &target_os, &target_arch)?;
+    // Get the pinned (or latest) release URL for this platform
+    let pin = get_tool_pin(tool, &target_os, &target_arch)?;
+    let download_url =
+        get_tool_download_url(tool, &target_os, &target_arch, pin.version.as_deref())?;
+
+    // Reuse a cached archive from a previous build, otherwise download and cache it
+    let cache_path = get_tool_cache_dir().join(get_cache_key(tool, &download_url));
+    let data = if cache_path.exists() {
+        println!(
+            "cargo:warning=Using cached {} archive: {}",
+            tool,
+            cache_path.display()
+        );
+        fs::read(&cache_path)?
+    } else {
+        let data = download_with_progress(tool, &download_url)?;
+        if let Err(e) = cache_archive(&cache_path, &data) {
+            println!("cargo:warning=Failed to cache {} archive: {}", tool, e);
+        }
+        data
+    };
 
-    // Download tool archive
-    let response = ureq::get(&download_url).call()?;
-    let mut data = Vec::new();
-    std::io::copy(&mut response.into_reader(), &mut data)?;
+    // A hash mismatch means a tampered or changed release, so fail the build outright
+    let actual_sha256 = sha256_hex(&data);
+    match &pin.sha256 {
+        Some(expected) if !expected.eq_ignore_ascii_case(&actual_sha256) => {
+            let _ = fs::remove_file(&cache_path);
+            panic!(
+                "Checksum mismatch for {} ({}): expected {}, got {}",
+                tool, download_url, expected, actual_sha256
+            );
+        }
+        Some(_) => println!("cargo:warning=Verified {} checksum", tool),
+        None => println!(
+            "cargo:warning=Resolved {} {} for {}-{} (sha256 {}), pin it in {} for reproducible builds",
+            tool,
+            get_release_tag(&download_url),
+            target_os,
+            target_arch,
+            actual_sha256,
+            TOOLS_LOCK_FILE
+        ),
+    }
 
     println!(
         "cargo:warning=Downloaded {} ({} bytes), extracting...",
@@ -88,6 +181,152 @@ fn prepare_tool_resource(tool: &str) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Pinned release version and expected archive hash for a tool
+struct ToolPin {
+    version: Option<String>,
+    sha256: Option<String>,
+}
+
+/// Read a tool's pin from env vars, falling back to the lock file
+fn get_tool_pin(
+    tool: &str,
+    target_os: &str,
+    target_arch: &str,
+) -> Result<ToolPin, Box<dyn std::error::Error>> {
+    let prefix = tool.to_uppercase();
+    let mut pin = ToolPin {
+        version: env::var(format!("{}_VERSION", prefix)).ok(),
+        sha256: env::var(format!("{}_SHA256", prefix)).ok(),
+    };
+
+    let lock_path = PathBuf::from(TOOLS_LOCK_FILE);
+    if lock_path.exists() {
+        let lock: serde_json::Value = serde_json::from_str(&fs::read_to_string(&lock_path)?)?;
+        let entry = &lock[tool];
+        let platform = format!("{}-{}", target_os, target_arch);
+
+        if pin.version.is_none() {
+            pin.version = entry["version"].as_str().map(str::to_string);
+        }
+        if pin.sha256.is_none() {
+            pin.sha256 = entry["sha256"][platform.as_str()]
+                .as_str()
+                .map(str::to_string);
+        }
+    }
+
+    Ok(pin)
+}
+
+/// Hex-encoded SHA-256 of a downloaded archive
+fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Release tag of a GitHub asset URL (".../download/<tag>/<asset>")
+fn get_release_tag(url: &str) -> &str {
+    url.rsplit('/').nth(1).unwrap_or("latest")
+}
+
+/// Copy a user-supplied tool binary into the resources directory
+fn copy_prebuilt_binary(
+    source: &PathBuf,
+    target: &PathBuf,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !source.is_file() {
+        return Err(format!("Pre-supplied binary not found: {}", source.display()).into());
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(source, target)?;
+
+    // Set executable permission on Unix
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(target, fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(())
+}
+
+/// Directory for downloaded archives that survives `resources/` being cleaned
+fn get_tool_cache_dir() -> PathBuf {
+    env::var("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("target"))
+        .join("tool-cache")
+}
+
+/// Cache file name for an asset URL, keyed by tool, release tag and asset name
+/// e.g. ".../download/v0.13.1/typst-x86_64-apple-darwin.tar.xz"
+///   -> "typst-v0.13.1-typst-x86_64-apple-darwin.tar.xz"
+fn get_cache_key(tool: &str, url: &str) -> String {
+    let asset_name = url.rsplit('/').next().unwrap_or("archive");
+    format!("{}-{}-{}", tool, get_release_tag(url), asset_name)
+}
+
+/// Write a downloaded archive to the cache atomically
+fn cache_archive(cache_path: &PathBuf, data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = cache_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Write to a temp name first so an interrupted build never leaves a truncated archive
+    let partial_path = cache_path.with_extension("partial");
+    fs::write(&partial_path, data)?;
+    fs::rename(&partial_path, cache_path)?;
+    Ok(())
+}
+
+/// Check whether the skip-download env var is set to a truthy value
+fn skip_download() -> bool {
+    env::var(SKIP_DOWNLOAD_ENV)
+        .map(|value| !value.is_empty() && value != "0" && value.to_lowercase() != "false")
+        .unwrap_or(false)
+}
+
+/// Download a file into memory, printing progress so slow builds don't look hung
+fn download_with_progress(tool: &str, url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let response = ureq::get(url).call()?;
+    let total = response
+        .header("Content-Length")
+        .and_then(|len| len.parse::<usize>().ok());
+
+    let mut reader = response.into_reader();
+    let mut data = Vec::with_capacity(total.unwrap_or(0));
+    let mut buffer = [0u8; 64 * 1024];
+    let mut next_report = PROGRESS_STEP;
+
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        data.extend_from_slice(&buffer[..read]);
+
+        if data.len() >= next_report {
+            let downloaded_mb = data.len() / (1024 * 1024);
+            match total {
+                Some(total) if total > 0 => println!(
+                    "cargo:warning=Downloading {}: {} MB / {} MB ({:.0}%)",
+                    tool,
+                    downloaded_mb,
+                    total / (1024 * 1024),
+                    data.len() as f64 / total as f64 * 100.0
+                ),
+                _ => println!("cargo:warning=Downloading {}: {} MB", tool, downloaded_mb),
+            }
+            next_report += PROGRESS_STEP;
+        }
+    }
+
+    Ok(data)
+}
+
 fn is_tool_available(tool_dir: &PathBuf, exe_name: &str) -> bool {
     if !tool_dir.exists() {
         return false;
@@ -130,10 +369,11 @@ fn get_tool_download_url(
     tool: &str,
     target_os: &str,
     target_arch: &str,
+    version: Option<&str>,
 ) -> Result<String, Box<dyn std::error::Error>> {
     match tool {
-        "pandoc" => get_pandoc_download_url(target_os, target_arch),
-        "typst" => get_typst_download_url(target_os, target_arch),
+        "pandoc" => get_pandoc_download_url(target_os, target_arch, version),
+        "typst" => get_typst_download_url(target_os, target_arch, version),
         _ => Err(format!("Unsupported tool: {}", tool).into()),
     }
 }
@@ -141,32 +381,175 @@ fn get_tool_download_url(
 fn get_pandoc_download_url(
     target_os: &str,
     target_arch: &str,
+    version: Option<&str>,
 ) -> Result<String, Box<dyn std::error::Error>> {
     // Use UNGH API for better reliability and mirroring
-    let api_url = "https://ungh.cc/repos/jgm/pandoc/releases/latest";
-    find_asset_download_url(api_url, &get_pandoc_asset_patterns(target_os, target_arch))
+    let release = get_release_data("jgm/pandoc", version)?;
+    find_asset_download_url(&release, &get_pandoc_asset_patterns(target_os, target_arch))
 }
 
 fn get_typst_download_url(
     target_os: &str,
     target_arch: &str,
+    version: Option<&str>,
 ) -> Result<String, Box<dyn std::error::Error>> {
     // Use UNGH API for Typst (same as Pandoc)
-    let api_url = "https://ungh.cc/repos/typst/typst/releases/latest";
-    find_asset_download_url(api_url, &[get_typst_asset_pattern(target_os, target_arch)])
+    let release = get_release_data("typst/typst", version)?;
+    find_asset_download_url(&release, &[get_typst_asset_pattern(target_os, target_arch)])
 }
 
-/// Unified asset finder for UNGH API responses
+/// Fetch the release data for a pinned version, or the latest release
+/// Uses UNGH, or the GitHub API directly when a token is supplied;
+/// GitHub releases are converted to the UNGH shape
+fn get_release_data(
+    repo: &str,
+    version: Option<&str>,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let token = env::var(GITHUB_TOKEN_ENV)
+        .ok()
+        .filter(|token| !token.trim().is_empty());
+
+    let Some(version) = version else {
+        let release = match &token {
+            Some(token) => {
+                let api_url = format!("https://api.github.com/repos/{}/releases/latest", repo);
+                to_ungh_release(&fetch_release_json(&api_url, Some(token))?)
+            }
+            None => {
+                let api_url = format!("https://ungh.cc/repos/{}/releases/latest", repo);
+                fetch_release_json(&api_url, None)?["release"].clone()
+            }
+        };
+        return validate_release(release, repo);
+    };
+
+    let releases = match &token {
+        Some(token) => {
+            let api_url = format!(
+                "https://api.github.com/repos/{}/releases?per_page=100",
+                repo
+            );
+            fetch_release_json(&api_url, Some(token))?
+                .as_array()
+                .map(|releases| releases.iter().map(to_ungh_release).collect::<Vec<_>>())
+        }
+        None => {
+            let api_url = format!("https://ungh.cc/repos/{}/releases", repo);
+            fetch_release_json(&api_url, None)?["releases"]
+                .as_array()
+                .cloned()
+        }
+    }
+    .ok_or_else(|| format!("Unexpected release list response for {}", repo))?;
+
+    // Tags may or may not carry a "v" prefix (typst does, pandoc doesn't)
+    let wanted = version.trim_start_matches('v');
+    let release = releases
+        .into_iter()
+        .find(|release| {
+            release["tag"]
+                .as_str()
+                .is_some_and(|tag| tag.trim_start_matches('v') == wanted)
+        })
+        .ok_or_else(|| format!("Release {} not found for {}", version, repo))?;
+    validate_release(release, repo)
+}
+
+/// GET a release API URL as JSON, retrying transient failures
+/// A rate-limit response is reported right away, since retrying won't help
+fn fetch_release_json(
+    url: &str,
+    token: Option<&str>,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let mut last_error = String::new();
+
+    for attempt in 1..=API_ATTEMPTS {
+        let mut request = ureq::get(url).set("Accept", "application/json");
+        if let Some(token) = token {
+            request = request.set("Authorization", &format!("Bearer {}", token));
+        }
+
+        match request.call() {
+            Ok(response) => {
+                return response
+                    .into_json()
+                    .map_err(|e| format!("Invalid JSON from {}: {}", url, e).into());
+            }
+            Err(ureq::Error::Status(status @ (403 | 429), response)) => {
+                let exhausted = response.header("x-ratelimit-remaining") == Some("0");
+                let body = response.into_string().unwrap_or_default();
+                if status == 429 || exhausted || body.to_lowercase().contains("rate limit") {
+                    println!(
+                        "cargo:warning=GitHub API rate limit exceeded for {}; set {} to raise the limit",
+                        url, GITHUB_TOKEN_ENV
+                    );
+                    return Err(format!("GitHub API rate limit exceeded ({})", url).into());
+                }
+                return Err(format!("{} returned HTTP {}: {}", url, status, body.trim()).into());
+            }
+            Err(ureq::Error::Status(status, _)) if status < 500 => {
+                return Err(format!("{} returned HTTP {}", url, status).into());
+            }
+            Err(e) => last_error = e.to_string(),
+        }
+
+        if attempt < API_ATTEMPTS {
+            println!(
+                "cargo:warning=Release API request failed ({}), retrying ({}/{})...",
+                last_error, attempt, API_ATTEMPTS
+            );
+            std::thread::sleep(Duration::from_secs(2u64.pow(attempt)));
+        }
+    }
+
+    Err(format!(
+        "Release API request to {} failed after {} attempts: {}",
+        url, API_ATTEMPTS, last_error
+    )
+    .into())
+}
+
+/// Convert a GitHub API release into the UNGH shape the asset finder expects
+fn to_ungh_release(release: &serde_json::Value) -> serde_json::Value {
+    let assets = release["assets"].as_array().map(|assets| {
+        assets
+            .iter()
+            .map(|asset| {
+                serde_json::json!({
+                    "name": asset["name"],
+                    "downloadUrl": asset["browser_download_url"],
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+
+    serde_json::json!({ "tag": release["tag_name"], "assets": assets })
+}
+
+/// Make sure a release response actually lists assets before it is searched
+fn validate_release(
+    release: serde_json::Value,
+    repo: &str,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    if release["assets"].is_array() {
+        return Ok(release);
+    }
+
+    Err(format!(
+        "Unexpected release response for {} (no assets): {}",
+        repo, release
+    )
+    .into())
+}
+
+/// Unified asset finder for UNGH release data
 fn find_asset_download_url(
-    api_url: &str,
+    release: &serde_json::Value,
     patterns: &[String],
 ) -> Result<String, Box<dyn std::error::Error>> {
-    let response = ureq::get(api_url).call()?;
-    let json: serde_json::Value = response.into_json()?;
-
-    let assets = json["release"]["assets"]
+    let assets = release["assets"]
         .as_array()
-        .ok_or_else(|| format!("No assets found at {}", api_url))?;
+        .ok_or_else(|| format!("No assets found for release {}", release["tag"]))?;
 
     for pattern in patterns {
         for asset in assets {
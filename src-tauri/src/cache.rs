@@ -0,0 +1,119 @@
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Subdirectory of the app cache directory holding cached conversion outputs
+const CONVERSION_CACHE_DIR: &str = "conversions";
+
+/// Get the directory cached conversion outputs are stored in
+fn get_cache_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let cache_dir = app_handle
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("Failed to get app cache directory: {}", e))?;
+
+    Ok(cache_dir.join(CONVERSION_CACHE_DIR))
+}
+
+/// Feed a file's contents into the hasher
+fn hash_file(hasher: &mut Sha256, path: &Path) -> Result<(), String> {
+    let mut file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    std::io::copy(&mut file, hasher)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(())
+}
+
+/// Compute the cache key of a conversion
+/// Covers the pandoc executable (path and modification time), the input file, the option
+/// argv and the contents of every file the argv refers to: bibliographies, templates,
+/// filters and so on. Changing any of them produces a different key
+/// `line_ending` is applied after pandoc runs rather than through argv, so it's hashed too
+pub fn conversion_cache_key(
+    pandoc_cmd: &str,
+    args: &[String],
+    input_file: &Path,
+    working_dir: &Path,
+    line_ending: &str,
+) -> Result<String, String> {
+    let mut hasher = Sha256::new();
+
+    hasher.update(pandoc_cmd.as_bytes());
+    // Updating pandoc in place must not serve outputs of the old version
+    if let Ok(modified) = std::fs::metadata(pandoc_cmd).and_then(|m| m.modified()) {
+        hasher.update(format!("{:?}", modified).as_bytes());
+    }
+
+    hasher.update(input_file.as_os_str().as_encoded_bytes());
+    hasher.update([0]);
+    hash_file(&mut hasher, input_file)?;
+
+    hasher.update(line_ending.as_bytes());
+    hasher.update([0]);
+
+    for arg in args {
+        hasher.update(arg.as_bytes());
+        hasher.update([0]);
+
+        // "--template=foo.tex" refers to foo.tex; bare arguments may be files themselves
+        let value = match arg.strip_prefix("--") {
+            Some(option) => option.split_once('=').map(|(_, value)| value),
+            None => Some(arg.as_str()),
+        };
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            let path = working_dir.join(value);
+            if path.is_file() {
+                hash_file(&mut hasher, &path)?;
+            }
+        }
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// Copy a cached output to `output_file`; returns false when nothing is cached for the key
+pub fn restore_cached_output(
+    app_handle: &AppHandle,
+    key: &str,
+    output_file: &Path,
+) -> Result<bool, String> {
+    let cached = get_cache_dir(app_handle)?.join(key);
+    if !cached.is_file() {
+        return Ok(false);
+    }
+
+    std::fs::copy(&cached, output_file)
+        .map_err(|e| format!("Failed to copy cached output: {}", e))?;
+    Ok(true)
+}
+
+/// Store a finished conversion's output under its cache key
+pub fn store_cached_output(
+    app_handle: &AppHandle,
+    key: &str,
+    output_file: &Path,
+) -> Result<(), String> {
+    let cache_dir = get_cache_dir(app_handle)?;
+    std::fs::create_dir_all(&cache_dir)
+        .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+
+    std::fs::copy(output_file, cache_dir.join(key))
+        .map_err(|e| format!("Failed to cache output: {}", e))?;
+    Ok(())
+}
+
+/// Remove all cached conversion outputs
+#[tauri::command]
+pub async fn clear_conversion_cache(app_handle: AppHandle) -> Result<(), String> {
+    let cache_dir = get_cache_dir(&app_handle)?;
+    if cache_dir.exists() {
+        std::fs::remove_dir_all(&cache_dir)
+            .map_err(|e| format!("Failed to remove conversion cache: {}", e))?;
+    }
+
+    Ok(())
+}
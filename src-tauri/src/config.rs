@@ -0,0 +1,161 @@
+use crate::types::{FormatProfile, PandocConfig, SourcePreference};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+const CONFIG_FILE_NAME: &str = "config.json";
+
+/// Get the path of the persisted config file in the app data directory
+fn get_config_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    Ok(app_data_dir.join(CONFIG_FILE_NAME))
+}
+
+/// Load the persisted config, falling back to defaults if missing or unreadable
+pub fn load_config(app_handle: &AppHandle) -> PandocConfig {
+    get_config_path(app_handle)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Persist the config to the app data directory
+pub fn save_config(app_handle: &AppHandle, config: &PandocConfig) -> Result<(), String> {
+    let path = get_config_path(app_handle)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    let content = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write config: {}", e))
+}
+
+/// Get the preferred PDF engine used when a conversion doesn't specify one
+#[tauri::command]
+pub async fn get_preferred_pdf_engine(app_handle: AppHandle) -> Option<String> {
+    load_config(&app_handle).preferred_pdf_engine
+}
+
+/// Set (or clear with None) the preferred PDF engine
+#[tauri::command]
+pub async fn set_preferred_pdf_engine(
+    engine: Option<String>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let engine = engine.filter(|e| !e.is_empty());
+
+    if let Some(ref engine) = engine {
+        if !crate::pandoc::is_pdf_engine_available(engine, &app_handle) {
+            return Err(format!("PDF engine '{}' is not available", engine));
+        }
+    }
+
+    let mut config = load_config(&app_handle);
+    config.preferred_pdf_engine = engine;
+    save_config(&app_handle, &config)
+}
+
+/// Get which pandoc installation is preferred (managed or system)
+#[tauri::command]
+pub async fn get_source_preference(app_handle: AppHandle) -> SourcePreference {
+    load_config(&app_handle).source_preference
+}
+
+/// Set which pandoc installation is preferred (managed or system)
+#[tauri::command]
+pub async fn set_source_preference(
+    preference: SourcePreference,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let mut config = load_config(&app_handle);
+    config.source_preference = preference;
+    save_config(&app_handle, &config)?;
+
+    // The cached path was chosen under the old preference
+    crate::pandoc::set_cached_pandoc_path(&app_handle, None);
+    Ok(())
+}
+
+/// Get the Typst package cache directory, None when typst uses its default
+#[tauri::command]
+pub async fn get_typst_package_path(app_handle: AppHandle) -> Option<String> {
+    load_config(&app_handle).typst_package_path
+}
+
+/// Set (or clear with None) the Typst package cache directory, e.g. a shared
+/// pre-populated cache for offline use
+#[tauri::command]
+pub async fn set_typst_package_path(
+    path: Option<String>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let path = path.filter(|p| !p.is_empty());
+
+    if let Some(ref path) = path {
+        if !std::path::Path::new(path).is_dir() {
+            return Err(format!("Typst package directory not found: {}", path));
+        }
+    }
+
+    let mut config = load_config(&app_handle);
+    config.typst_package_path = path;
+    save_config(&app_handle, &config)
+}
+
+/// Get the default options profile for an output format
+#[tauri::command]
+pub async fn get_format_profile(format: String, app_handle: AppHandle) -> Option<FormatProfile> {
+    load_config(&app_handle).format_profiles.remove(&format)
+}
+
+/// Set (or remove with None) the default options profile for an output format
+#[tauri::command]
+pub async fn set_format_profile(
+    format: String,
+    profile: Option<FormatProfile>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    if format.is_empty() {
+        return Err("Format must not be empty".to_string());
+    }
+
+    let mut config = load_config(&app_handle);
+    match profile {
+        Some(profile) => {
+            config.format_profiles.insert(format, profile);
+        }
+        None => {
+            config.format_profiles.remove(&format);
+        }
+    }
+    save_config(&app_handle, &config)
+}
+
+/// Whether downloads race the mirrors instead of trying them in order
+#[tauri::command]
+pub async fn get_race_mirrors(app_handle: AppHandle) -> bool {
+    load_config(&app_handle).race_mirrors
+}
+
+/// Enable or disable racing the download mirrors
+#[tauri::command]
+pub async fn set_race_mirrors(enabled: bool, app_handle: AppHandle) -> Result<(), String> {
+    let mut config = load_config(&app_handle);
+    config.race_mirrors = enabled;
+    save_config(&app_handle, &config)
+}
+
+/// List all format profiles, keyed by output format
+#[tauri::command]
+pub async fn list_format_profiles(app_handle: AppHandle) -> HashMap<String, FormatProfile> {
+    load_config(&app_handle).format_profiles
+}
@@ -0,0 +1,93 @@
+use crate::types::HistoryEntry;
+use std::io::Write;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+const HISTORY_FILE_NAME: &str = "history.jsonl";
+const ROTATED_HISTORY_FILE_NAME: &str = "history.jsonl.1";
+/// Rotate the history file once it grows past this size
+const MAX_HISTORY_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Get the path of a history file in the app data directory
+fn get_history_path(app_handle: &AppHandle, file_name: &str) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    Ok(app_data_dir.join(file_name))
+}
+
+/// Append a conversion to the history, rotating the file when it gets too large
+pub fn record_conversion(app_handle: &AppHandle, entry: &HistoryEntry) -> Result<(), String> {
+    let path = get_history_path(app_handle, HISTORY_FILE_NAME)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create history directory: {}", e))?;
+    }
+
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() > MAX_HISTORY_FILE_SIZE) {
+        let rotated = get_history_path(app_handle, ROTATED_HISTORY_FILE_NAME)?;
+        std::fs::rename(&path, rotated)
+            .map_err(|e| format!("Failed to rotate history file: {}", e))?;
+    }
+
+    let line = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize history entry: {}", e))?;
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open history file: {}", e))?;
+
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write history entry: {}", e))
+}
+
+/// Read the entries of one history file, skipping lines that don't parse
+fn read_history_file(path: &PathBuf) -> Vec<HistoryEntry> {
+    std::fs::read_to_string(path)
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Get past conversions, most recent first
+#[tauri::command]
+pub async fn get_conversion_history(
+    limit: Option<usize>,
+    app_handle: AppHandle,
+) -> Result<Vec<HistoryEntry>, String> {
+    // Older entries live in the rotated file
+    let mut entries = read_history_file(&get_history_path(&app_handle, ROTATED_HISTORY_FILE_NAME)?);
+    entries.extend(read_history_file(&get_history_path(
+        &app_handle,
+        HISTORY_FILE_NAME,
+    )?));
+
+    entries.reverse();
+    if let Some(limit) = limit {
+        entries.truncate(limit);
+    }
+
+    Ok(entries)
+}
+
+/// Delete all recorded conversions
+#[tauri::command]
+pub async fn clear_conversion_history(app_handle: AppHandle) -> Result<(), String> {
+    for file_name in [HISTORY_FILE_NAME, ROTATED_HISTORY_FILE_NAME] {
+        let path = get_history_path(&app_handle, file_name)?;
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove history file: {}", e))?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,38 +1,134 @@
+mod cache;
+mod config;
+mod history;
+mod logging;
 mod manager;
+mod network;
 mod pandoc;
 mod types;
 mod utils;
+mod watch;
 
+use cache::clear_conversion_cache;
+use config::{
+    get_format_profile, get_preferred_pdf_engine, get_race_mirrors, get_source_preference,
+    get_typst_package_path, list_format_profiles, set_format_profile, set_preferred_pdf_engine,
+    set_race_mirrors, set_source_preference, set_typst_package_path,
+};
+use history::{clear_conversion_history, get_conversion_history};
+use logging::{export_support_bundle, get_log_path};
 use manager::{
-    check_bundled_pandoc_update, create_and_validate_custom_manager, discover_pandoc_sources,
-    download_pandoc, download_typst, extract_pandoc_archive, get_best_pandoc_manager,
-    get_latest_pandoc_release, get_latest_typst_release_info, get_pandoc_releases,
-    get_version_info, update_bundled_pandoc, update_managed_pandoc, update_managed_typst,
+    cancel_all_downloads, check_bundled_pandoc_update, check_bundled_typst_update,
+    cleanup_temp_downloads, clear_quarantine, create_and_validate_custom_manager, delete_archive,
+    discover_pandoc_sources, download_pandoc, download_pandoc_temp, download_typst,
+    extract_pandoc_archive, fetch_release, get_all_pandoc_versions, get_app_tool_versions,
+    get_best_pandoc_manager, get_latest_pandoc_release, get_latest_typst_release_info,
+    get_pandoc_releases, get_typst_releases, get_typst_version_info, get_version_info,
+    import_managed_archive, list_downloaded_archives, make_executable, reinstall_for_current_arch,
+    test_mirrors, update_bundled_pandoc, update_managed_pandoc, update_managed_typst,
+    verify_managed_install, DownloadRegistry,
 };
+use network::{get_proxy_config, set_proxy_config, test_connectivity};
 use pandoc::{
-    check_bundled_typst, check_pandoc_version, check_portable_pandoc, convert_with_pandoc,
-    get_available_pdf_engines, get_bundled_pandoc_path, get_bundled_typst_path, get_pandoc_info,
-    get_pandoc_info_with_portable, get_pandoc_path, install_portable_pandoc, setup_bundled_pandoc,
-    validate_pandoc_path,
+    analyze_document, benchmark_conversion, can_convert_to, cancel_conversion, check_bundled_typst,
+    check_pandoc_version, check_portable_pandoc, convert_and_open, convert_bibliography,
+    convert_clipboard, convert_multi_output, convert_split_by_heading, convert_text,
+    convert_to_bytes, convert_with_pandoc, detect_latex_distributions, diagnose_custom_path,
+    diff_conversions, export_default_data_file, from_json_ast, get_available_pdf_engines,
+    get_bundled_pandoc_path, get_bundled_typst_path, get_conversion_matrix,
+    get_formats_requiring_engine, get_pandoc_info, get_pandoc_info_with_portable, get_pandoc_path,
+    get_search_paths_debug, install_portable_pandoc, invalidate_pandoc_cache, list_available_fonts,
+    list_highlight_styles, list_templates, read_default_template, read_metadata,
+    render_first_page_image, setup_bundled_pandoc, shutdown_cleanup, to_json_ast,
+    validate_document, validate_pandoc_path, warm_pdf_engine, ConversionRegistry, PandocPathCache,
+    WarmedPdfEngines,
 };
+use watch::{stop_watch, watch_convert, WatchRegistry};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        .manage(PandocPathCache::default())
+        .manage(ConversionRegistry::default())
+        .manage(WarmedPdfEngines::default())
+        .manage(WatchRegistry::default())
+        .manage(DownloadRegistry::default())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .setup(|app| {
+            logging::init(app.handle());
+            network::init(app.handle());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // Pandoc detection and conversion commands
             get_pandoc_info,
             get_pandoc_info_with_portable,
             get_pandoc_path,
+            get_search_paths_debug,
+            list_templates,
+            list_highlight_styles,
+            export_default_data_file,
+            read_default_template,
             validate_pandoc_path,
+            diagnose_custom_path,
+            invalidate_pandoc_cache,
             convert_with_pandoc,
+            convert_multi_output,
+            convert_split_by_heading,
+            convert_to_bytes,
+            convert_bibliography,
+            render_first_page_image,
+            convert_text,
+            convert_clipboard,
+            to_json_ast,
+            read_metadata,
+            from_json_ast,
+            convert_and_open,
+            cancel_conversion,
+            shutdown_cleanup,
+            validate_document,
+            analyze_document,
+            benchmark_conversion,
+            diff_conversions,
+            warm_pdf_engine,
             check_pandoc_version,
             get_available_pdf_engines,
+            detect_latex_distributions,
+            list_available_fonts,
+            get_formats_requiring_engine,
+            can_convert_to,
+            get_conversion_matrix,
+            // Watch mode commands
+            watch_convert,
+            stop_watch,
+            // Config commands
+            get_preferred_pdf_engine,
+            set_preferred_pdf_engine,
+            get_source_preference,
+            set_source_preference,
+            get_format_profile,
+            set_format_profile,
+            list_format_profiles,
+            get_typst_package_path,
+            set_typst_package_path,
+            get_race_mirrors,
+            set_race_mirrors,
+            // Conversion history commands
+            get_conversion_history,
+            clear_conversion_history,
+            clear_conversion_cache,
+            // Logging commands
+            get_log_path,
+            export_support_bundle,
+            // Network commands
+            get_proxy_config,
+            set_proxy_config,
+            test_connectivity,
             // Portable Pandoc commands
             check_portable_pandoc,
             install_portable_pandoc,
@@ -47,18 +143,42 @@ pub fn run() {
             get_pandoc_releases,
             get_version_info,
             download_pandoc,
+            download_pandoc_temp,
+            cancel_all_downloads,
+            cleanup_temp_downloads,
             extract_pandoc_archive,
+            list_downloaded_archives,
+            delete_archive,
+            import_managed_archive,
+            clear_quarantine,
+            make_executable,
             discover_pandoc_sources,
             get_best_pandoc_manager,
+            get_all_pandoc_versions,
+            test_mirrors,
             create_and_validate_custom_manager,
             update_bundled_pandoc,
             check_bundled_pandoc_update,
+            check_bundled_typst_update,
+            get_app_tool_versions,
+            reinstall_for_current_arch,
+            verify_managed_install,
             // Typst commands
             download_typst,
             get_latest_typst_release_info,
+            get_typst_releases,
+            get_typst_version_info,
+            // Generic release commands
+            fetch_release,
             update_managed_pandoc,
             update_managed_typst
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // Don't leave pandoc processes or half-downloaded archives behind
+            if let tauri::RunEvent::Exit = event {
+                pandoc::cleanup_active_operations(app_handle);
+            }
+        });
 }
@@ -0,0 +1,126 @@
+use std::io::Write;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Prefix of the daily log files; the appender adds a ".YYYY-MM-DD" suffix
+const LOG_FILE_NAME: &str = "pandoc-desktop.log";
+
+/// Get the directory the log files are written to
+fn get_log_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to get app log directory: {}", e))
+}
+
+/// Log to a daily rotated file in the app log directory, and to stdout for development
+pub fn init(app_handle: &AppHandle) {
+    let file_layer = get_log_dir(app_handle).ok().map(|log_dir| {
+        let appender = tracing_appender::rolling::daily(log_dir, LOG_FILE_NAME);
+        tracing_subscriber::fmt::layer()
+            .with_writer(appender)
+            .with_ansi(false)
+    });
+
+    let result = tracing_subscriber::registry()
+        .with(LevelFilter::INFO)
+        .with(file_layer)
+        .with(tracing_subscriber::fmt::layer())
+        .try_init();
+
+    if let Err(e) = result {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+}
+
+/// List all log files, oldest first
+fn list_log_files(app_handle: &AppHandle) -> Result<Vec<PathBuf>, String> {
+    let log_dir = get_log_dir(app_handle)?;
+    let Ok(entries) = std::fs::read_dir(&log_dir) else {
+        return Ok(Vec::new());
+    };
+
+    // Date suffixes sort chronologically
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with(LOG_FILE_NAME))
+        })
+        .map(|entry| entry.path())
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Get the path of the current log file
+#[tauri::command]
+pub async fn get_log_path(app_handle: AppHandle) -> Result<String, String> {
+    list_log_files(&app_handle)?
+        .pop()
+        .map(|path| path.to_string_lossy().to_string())
+        .ok_or_else(|| "No log file has been written yet".to_string())
+}
+
+/// Collect environment details that help with bug reports
+async fn collect_diagnostics(app_handle: &AppHandle) -> serde_json::Value {
+    let pandoc = crate::pandoc::get_pandoc_info_with_portable(app_handle.clone(), None).await;
+
+    serde_json::json!({
+        "app_version": app_handle.package_info().version.to_string(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "config": crate::config::load_config(app_handle),
+        "pandoc": match pandoc {
+            Ok(info) => serde_json::json!(info),
+            Err(e) => serde_json::json!({ "error": e }),
+        },
+        "search_paths": crate::pandoc::get_search_paths_debug().await,
+        "latex": crate::pandoc::detect_latex_distributions().await,
+    })
+}
+
+/// Write a zip with all log files and a diagnostics report to `output_path`
+/// Returns the path of the written bundle
+#[tauri::command]
+pub async fn export_support_bundle(
+    output_path: String,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let diagnostics = serde_json::to_string_pretty(&collect_diagnostics(&app_handle).await)
+        .map_err(|e| format!("Failed to serialize diagnostics: {}", e))?;
+    let log_files = list_log_files(&app_handle)?;
+
+    let file = std::fs::File::create(&output_path)
+        .map_err(|e| format!("Failed to create support bundle: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+
+    zip.start_file("diagnostics.json", options)
+        .map_err(|e| format!("Failed to write support bundle: {}", e))?;
+    zip.write_all(diagnostics.as_bytes())
+        .map_err(|e| format!("Failed to write support bundle: {}", e))?;
+
+    for log_file in log_files {
+        let Some(name) = log_file.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let content = std::fs::read(&log_file)
+            .map_err(|e| format!("Failed to read {}: {}", log_file.display(), e))?;
+
+        zip.start_file(format!("logs/{}", name), options)
+            .map_err(|e| format!("Failed to write support bundle: {}", e))?;
+        zip.write_all(&content)
+            .map_err(|e| format!("Failed to write support bundle: {}", e))?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finish support bundle: {}", e))?;
+
+    Ok(output_path)
+}
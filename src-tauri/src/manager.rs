@@ -1,12 +1,21 @@
-use crate::types::{GithubAsset, GithubRelease, PandocInfo, VersionInfo};
-use crate::utils::format_file_size;
+use crate::types::{
+    AppToolVersions, ArchiveInfo, DownloadProgress, DownloadResult, ExtractProgress, GithubAsset,
+    GithubRelease, InstallHealth, MirrorStatus, PandocInfo, ToolVersion, VersionInfo,
+};
+use crate::utils::{compare_versions, format_file_size, parse_version, strip_version_prefix};
+use futures_util::stream::{FuturesUnordered, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::io::Write;
-use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use std::collections::{HashMap, VecDeque};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_http::reqwest;
+use tokio::sync::oneshot;
 
 const UNGH_API_BASE: &str = "https://ungh.cc/repos";
+const GITHUB_API_BASE: &str = "https://api.github.com/repos";
 const PANDOC_REPO: &str = "jgm/pandoc";
 const TYPST_REPO: &str = "typst/typst";
 
@@ -24,12 +33,27 @@ pub enum DownloadType {
     Typst,
 }
 
+/// Timeout for the lightweight mirror probe requests
+const MIRROR_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Timeout for a whole download through a mirror, before moving on to the next one
+const MIRROR_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Timeout for the final direct GitHub download, which has no fallback left
+const DIRECT_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(900);
+
+/// Known release asset used to check mirror reachability (only HEAD is requested)
+const MIRROR_TEST_URL: &str =
+    "https://github.com/jgm/pandoc/releases/download/3.7.0.2/pandoc-3.7.0.2-windows-x86_64.zip";
+
 /// Configuration for downloads
 #[derive(Debug, Clone)]
 pub struct DownloadConfig {
     pub target_os: String,
     pub target_arch: String,
     pub use_mirrors: bool,
+    /// Probe all mirrors concurrently and download from the fastest (off by default)
+    pub race_mirrors: bool,
 }
 
 impl DownloadConfig {
@@ -37,8 +61,17 @@ impl DownloadConfig {
     pub fn current_platform() -> Self {
         Self {
             target_os: std::env::consts::OS.to_string(),
-            target_arch: std::env::consts::ARCH.to_string(),
+            target_arch: crate::utils::get_native_arch(),
             use_mirrors: true,
+            race_mirrors: false,
+        }
+    }
+
+    /// Download config for the current platform with the user's mirror settings applied
+    pub fn from_settings(app_handle: &AppHandle) -> Self {
+        Self {
+            race_mirrors: crate::config::load_config(app_handle).race_mirrors,
+            ..Self::current_platform()
         }
     }
 }
@@ -62,6 +95,19 @@ pub struct PandocManager {
     pub available: bool,
 }
 
+/// Version reported by one pandoc source, for comparing installs side by side
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceVersion {
+    pub source: PandocSource,
+    pub path: Option<String>,
+    /// None when the source isn't available
+    pub version: Option<String>,
+    /// The source conversions currently use
+    pub is_active: bool,
+    /// Older than the newest version among all sources
+    pub is_outdated: bool,
+}
+
 impl PandocManager {
     /// Create a new PandocManager
     pub fn new(source: PandocSource) -> Self {
@@ -123,7 +169,15 @@ fn get_managed_pandoc_path(app_handle: &AppHandle) -> Option<PathBuf> {
         "pandoc"
     };
 
-    // Priority 1: Check resources directory (bundled with app during build)
+    // Priority 1: Check app data directory (updates installed when resources aren't writable)
+    if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
+        let updated_pandoc_dir = app_data_dir.join("pandoc");
+        if let Some(path) = find_pandoc_in_directory(&updated_pandoc_dir, &pandoc_exe) {
+            return Some(path);
+        }
+    }
+
+    // Priority 2: Check resources directory (bundled with app during build)
     if let Ok(resource_dir) = app_handle.path().resource_dir() {
         let resource_pandoc_dir = resource_dir.join("pandoc");
         if let Some(path) = find_pandoc_in_directory(&resource_pandoc_dir, &pandoc_exe) {
@@ -131,7 +185,7 @@ fn get_managed_pandoc_path(app_handle: &AppHandle) -> Option<PathBuf> {
         }
     }
 
-    // Priority 2: Check app data directory (portable downloaded by user)
+    // Priority 3: Check app data directory (portable downloaded by user)
     if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
         let portable_pandoc_dir = app_data_dir.join("pandoc-portable");
         if let Some(path) = find_pandoc_in_directory(&portable_pandoc_dir, &pandoc_exe) {
@@ -143,8 +197,9 @@ fn get_managed_pandoc_path(app_handle: &AppHandle) -> Option<PathBuf> {
 }
 
 /// Find pandoc executable in a directory (with common subdirectory patterns)
-fn find_pandoc_in_directory(base_dir: &PathBuf, exe_name: &str) -> Option<PathBuf> {
-    if !base_dir.exists() {
+pub fn find_pandoc_in_directory(base_dir: &PathBuf, exe_name: &str) -> Option<PathBuf> {
+    // App data can live on a network or removable drive; an unreachable one counts as missing
+    if !crate::utils::path_exists_with_timeout(base_dir, crate::utils::PATH_CHECK_TIMEOUT) {
         return None;
     }
 
@@ -161,6 +216,9 @@ fn find_pandoc_in_directory(base_dir: &PathBuf, exe_name: &str) -> Option<PathBu
     }
 
     // Check for pandoc in any subdirectory (version directories are common)
+    // read_dir order is unspecified, so pick the newest version rather than the first hit
+    let mut newest: Option<(PathBuf, Option<String>)> = None;
+
     if let Ok(entries) = std::fs::read_dir(base_dir) {
         for entry in entries.flatten() {
             if entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
@@ -169,37 +227,70 @@ fn find_pandoc_in_directory(base_dir: &PathBuf, exe_name: &str) -> Option<PathBu
                     entry.path().join("bin").join(exe_name),
                 ];
 
-                for path in sub_paths {
-                    if path.exists() {
-                        return Some(path);
+                let Some(path) = sub_paths.into_iter().find(|path| path.exists()) else {
+                    continue;
+                };
+                let version = get_candidate_version(&entry.path(), &path);
+
+                let is_newer = match (&newest, &version) {
+                    (None, _) => true,
+                    (Some((_, None)), Some(_)) => true,
+                    (Some((_, Some(best))), Some(version)) => {
+                        compare_versions(best, version) == Some(std::cmp::Ordering::Less)
                     }
+                    _ => false,
+                };
+
+                if is_newer {
+                    newest = Some((path, version));
                 }
             }
         }
     }
 
-    None
+    newest.map(|(path, _)| path)
+}
+
+/// Get the version of a pandoc found in a version directory
+/// Uses the directory name ("pandoc-3.7.0.2") when possible, otherwise runs `--version`
+fn get_candidate_version(version_dir: &Path, exe_path: &Path) -> Option<String> {
+    let is_version = |version: &String| version.starts_with(|c: char| c.is_ascii_digit());
+
+    let dir_name = version_dir.file_name()?.to_string_lossy();
+    let from_name = parse_version(dir_name.trim_start_matches("pandoc-"));
+    if is_version(&from_name) {
+        return Some(from_name);
+    }
+
+    let output = crate::utils::create_hidden_command(exe_path)
+        .arg("--version")
+        .output()
+        .ok()?;
+
+    Some(parse_version(&String::from_utf8_lossy(&output.stdout))).filter(is_version)
 }
 
 /// Validate pandoc executable and get its info
 async fn validate_pandoc_executable(path: &PathBuf) -> Result<PandocInfo, String> {
-    if !path.exists() {
-        return Err("Pandoc executable not found".to_string());
+    if !crate::utils::path_exists_with_timeout(path, crate::utils::PATH_CHECK_TIMEOUT) {
+        return Err("Pandoc executable not found or its drive is unreachable".to_string());
     }
 
-    let output = crate::utils::create_hidden_command(&path.to_string_lossy())
+    let output = crate::utils::create_hidden_command(path)
         .arg("--version")
         .output()
-        .map_err(|e| format!("Failed to execute pandoc: {}", e))?;
+        .map_err(|e| crate::utils::describe_exec_error(&path.to_string_lossy(), &e))?;
 
     if !output.status.success() {
-        return Err("Pandoc failed to execute".to_string());
+        // Gatekeeper kills quarantined binaries instead of failing to start them
+        return Err(crate::utils::quarantine_error(path)
+            .unwrap_or_else(|| "Pandoc failed to execute".to_string()));
     }
 
     let version_text = String::from_utf8(output.stdout)
         .map_err(|_| "Failed to read pandoc version output".to_string())?;
 
-    let version = extract_version_from_output(&version_text);
+    let version = parse_version(&version_text);
     let path_str = path.to_string_lossy().to_string();
 
     // Get supported formats using the pandoc module function
@@ -224,99 +315,17 @@ async fn validate_pandoc_executable(path: &PathBuf) -> Result<PandocInfo, String
         supported_output_formats: output_formats,
         detected_paths: vec![],
         search_paths: vec![],
+        user_data_dir: crate::utils::parse_user_data_dir(&version_text),
+        architecture: crate::utils::detect_architecture(&path_str, &version_text),
+        compiled_features: crate::utils::parse_compiled_features(&version_text).unwrap_or_default(),
     })
 }
 
-/// Extract version number from pandoc version output
-fn extract_version_from_output(output: &str) -> String {
-    let first_line = output.lines().next().unwrap_or("Unknown");
-
-    // Handle common format: "pandoc.exe 3.7.0.2" or "pandoc 3.7.0.2"
-    let parts: Vec<&str> = first_line.trim().split_whitespace().collect();
-
-    // Look for version number in the parts (should be after program name)
-    for part in &parts {
-        // Skip program names
-        if part.contains("pandoc") {
-            continue;
-        }
-
-        // Check if this part looks like a version number (starts with digit)
-        if part.chars().next().map_or(false, |c| c.is_ascii_digit()) {
-            // Extract version pattern (digits and dots)
-            let mut version = String::new();
-            for ch in part.chars() {
-                if ch.is_ascii_digit() || ch == '.' {
-                    version.push(ch);
-                } else {
-                    break;
-                }
-            }
-
-            if !version.is_empty() && version != "." {
-                return version;
-            }
-        }
-    }
-
-    // Fallback: return first line with pandoc names removed
-    first_line
-        .replace("pandoc.exe", "")
-        .replace("pandoc", "")
-        .trim()
-        .to_string()
-}
-
-/// Extract clean version number from version string (simplified approach)
-/// Examples:
-/// "pandoc.exe 3.7.0.2" -> "3.7.0.2"
-/// "pandoc 3.7.0.2" -> "3.7.0.2"
-/// "v3.7.0.2" -> "3.7.0.2"
-/// "3.7.0.2" -> "3.7.0.2"
-fn normalize_version(version_str: &str) -> String {
-    let text = version_str.trim();
-
-    // Split by whitespace and look for version-like strings
-    for part in text.split_whitespace() {
-        // Skip common prefixes
-        if part.contains("pandoc") {
-            continue;
-        }
-
-        let part = part.trim_start_matches('v');
-
-        // Check if this looks like a version number (starts with digit)
-        if part.chars().next().map_or(false, |c| c.is_ascii_digit()) {
-            // Extract version pattern manually
-            let mut version = String::new();
-            for ch in part.chars() {
-                if ch.is_ascii_digit() || ch == '.' {
-                    version.push(ch);
-                } else {
-                    break;
-                }
-            }
-
-            // Make sure we have a valid version (not just dots)
-            if !version.is_empty() && version.chars().any(|c| c.is_ascii_digit()) {
-                return version;
-            }
-        }
-    }
-
-    // Fallback: clean up the entire string
-    let cleaned = text
-        .replace("pandoc.exe", "")
-        .replace("pandoc", "")
-        .trim()
-        .trim_start_matches('v')
-        .trim()
-        .to_string();
-
-    if !cleaned.is_empty() {
-        cleaned
-    } else {
-        text.to_string()
+/// Check whether `latest` is newer than `current`, falling back to string inequality
+fn is_newer_version(current: &str, latest: &str) -> bool {
+    match compare_versions(current, latest) {
+        Some(ordering) => ordering == std::cmp::Ordering::Less,
+        None => parse_version(current) != parse_version(latest),
     }
 }
 
@@ -331,12 +340,11 @@ fn construct_mirror_url(mirror: &str, original_url: &str) -> String {
     }
 }
 
-/// Get latest release information from UNGH API
-#[tauri::command]
-pub async fn get_latest_pandoc_release() -> Result<GithubRelease, String> {
-    let url = format!("{}/{}/releases/latest", UNGH_API_BASE, PANDOC_REPO);
-
-    let response = reqwest::get(&url)
+/// Fetch and parse a JSON document from the UNGH API
+async fn fetch_ungh_json(url: &str) -> Result<serde_json::Value, String> {
+    let response = crate::network::http_client()
+        .get(url)
+        .send()
         .await
         .map_err(|e| format!("Failed to fetch release info: {}", e))?;
 
@@ -347,39 +355,54 @@ pub async fn get_latest_pandoc_release() -> Result<GithubRelease, String> {
         ));
     }
 
+    let is_json = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("json"));
+
     let response_text = response
         .text()
         .await
         .map_err(|e| format!("Failed to read response: {}", e))?;
 
-    let api_response: serde_json::Value =
-        serde_json::from_str(&response_text).map_err(|e| format!("Failed to parse JSON: {}", e))?;
-
-    // UNGH wraps the release in a "release" field
-    let release_data = api_response["release"].clone();
-
-    if release_data.is_null() {
-        return Err("No release data found in response".to_string());
+    // UNGH serves rate-limit and maintenance pages as HTML with a 200 status
+    if !is_json {
+        return Err(unexpected_ungh_response(&response_text));
     }
 
-    // Parse UNGH response format
-    let release = parse_ungh_release(release_data)?;
-    Ok(release)
+    serde_json::from_str(&response_text).map_err(|_| unexpected_ungh_response(&response_text))
 }
 
-/// Get all available releases
-#[tauri::command]
-pub async fn get_pandoc_releases(limit: Option<u32>) -> Result<Vec<GithubRelease>, String> {
-    let _page_limit = limit.unwrap_or(10);
-    let url = format!("{}/{}/releases", UNGH_API_BASE, PANDOC_REPO);
+/// Build an error for a non-JSON or malformed UNGH response, including a snippet of the body
+fn unexpected_ungh_response(body: &str) -> String {
+    const SNIPPET_LEN: usize = 200;
 
-    let response = reqwest::get(&url)
+    let trimmed = body.trim();
+    let snippet: String = trimmed.chars().take(SNIPPET_LEN).collect();
+    let ellipsis = if trimmed.chars().count() > SNIPPET_LEN {
+        "..."
+    } else {
+        ""
+    };
+
+    format!("UNGH returned unexpected response: {}{}", snippet, ellipsis)
+}
+
+/// Fetch and parse a JSON document from the GitHub REST API
+async fn fetch_github_json(url: &str) -> Result<serde_json::Value, String> {
+    // GitHub rejects API requests that don't carry a User-Agent
+    let response = crate::network::http_client()
+        .get(url)
+        .header(reqwest::header::USER_AGENT, "pandoc-desktop")
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
         .await
-        .map_err(|e| format!("Failed to fetch releases: {}", e))?;
+        .map_err(|e| format!("Failed to fetch release info from GitHub: {}", e))?;
 
     if !response.status().is_success() {
         return Err(format!(
-            "API request failed with status: {}",
+            "GitHub API request failed with status: {}",
             response.status()
         ));
     }
@@ -387,29 +410,194 @@ pub async fn get_pandoc_releases(limit: Option<u32>) -> Result<Vec<GithubRelease
     let response_text = response
         .text()
         .await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
+        .map_err(|e| format!("Failed to read GitHub response: {}", e))?;
+
+    serde_json::from_str(&response_text)
+        .map_err(|e| format!("Failed to parse GitHub response: {}", e))
+}
+
+/// Get latest release information for any `owner/name` repository
+/// Tries UNGH first for its caching, falling back to the GitHub API
+pub async fn get_latest_release(repo: &str) -> Result<GithubRelease, String> {
+    match get_latest_ungh_release(repo).await {
+        Ok(release) => Ok(release),
+        Err(ungh_error) => {
+            tracing::warn!(
+                repo,
+                error = %ungh_error,
+                "UNGH unavailable, falling back to GitHub API"
+            );
+            get_latest_github_release(repo)
+                .await
+                .map_err(|e| format!("{}; {}", ungh_error, e))
+        }
+    }
+}
+
+/// Get available releases for any `owner/name` repository
+/// Tries UNGH first for its caching, falling back to the GitHub API
+pub async fn get_releases(repo: &str, limit: Option<u32>) -> Result<Vec<GithubRelease>, String> {
+    match get_ungh_releases(repo, limit).await {
+        Ok(releases) => Ok(releases),
+        Err(ungh_error) => {
+            tracing::warn!(
+                repo,
+                error = %ungh_error,
+                "UNGH unavailable, falling back to GitHub API"
+            );
+            get_github_releases(repo, limit)
+                .await
+                .map_err(|e| format!("{}; {}", ungh_error, e))
+        }
+    }
+}
+
+/// Get the release with a specific tag for any `owner/name` repository
+/// Tries UNGH first for its caching, falling back to the GitHub API
+pub async fn get_release_by_tag(repo: &str, tag: &str) -> Result<GithubRelease, String> {
+    match get_ungh_release_by_tag(repo, tag).await {
+        Ok(release) => Ok(release),
+        Err(ungh_error) => {
+            tracing::warn!(
+                repo,
+                tag,
+                error = %ungh_error,
+                "UNGH lookup failed, falling back to GitHub API"
+            );
+            get_github_release_by_tag(repo, tag)
+                .await
+                .map_err(|e| format!("{}; {}", ungh_error, e))
+        }
+    }
+}
 
-    let api_response: serde_json::Value =
-        serde_json::from_str(&response_text).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+/// Get the release with a specific tag for any `owner/name` repository from UNGH API
+/// UNGH has no per-tag endpoint, so the tag is looked up in its (cached) release list
+async fn get_ungh_release_by_tag(repo: &str, tag: &str) -> Result<GithubRelease, String> {
+    get_ungh_releases(repo, None)
+        .await?
+        .into_iter()
+        .find(|release| release.tag_name == tag)
+        .ok_or_else(|| format!("Release {} not listed by UNGH", tag))
+}
+
+/// Get latest release information for any `owner/name` repository from UNGH API
+async fn get_latest_ungh_release(repo: &str) -> Result<GithubRelease, String> {
+    let url = format!("{}/{}/releases/latest", UNGH_API_BASE, repo);
+    let api_response = fetch_ungh_json(&url).await?;
+
+    // UNGH wraps the release in a "release" field
+    let release_data = api_response["release"].clone();
+
+    if !release_data.is_object() {
+        return Err(unexpected_ungh_response(&api_response.to_string()));
+    }
+
+    parse_ungh_release(repo, release_data)
+}
+
+/// Get available releases for any `owner/name` repository from UNGH API
+async fn get_ungh_releases(repo: &str, limit: Option<u32>) -> Result<Vec<GithubRelease>, String> {
+    let url = format!("{}/{}/releases", UNGH_API_BASE, repo);
+    let api_response = fetch_ungh_json(&url).await?;
 
     // UNGH wraps the releases in a "releases" field
     let releases_data = api_response["releases"]
         .as_array()
-        .ok_or("No releases array found in response")?;
+        .ok_or_else(|| unexpected_ungh_response(&api_response.to_string()))?;
 
     let mut releases = Vec::new();
     for data in releases_data {
-        if let Ok(release) = parse_ungh_release(data.clone()) {
+        if let Ok(release) = parse_ungh_release(repo, data.clone()) {
             releases.push(release);
         }
     }
 
+    if let Some(limit) = limit {
+        releases.truncate(limit as usize);
+    }
+
+    Ok(releases)
+}
+
+/// Get latest release information for any `owner/name` repository from the GitHub API
+async fn get_latest_github_release(repo: &str) -> Result<GithubRelease, String> {
+    let url = format!("{}/{}/releases/latest", GITHUB_API_BASE, repo);
+    let data = fetch_github_json(&url).await?;
+
+    parse_github_release(&data)
+}
+
+/// Get the release for a specific tag of any `owner/name` repository from the GitHub API
+async fn get_github_release_by_tag(repo: &str, tag: &str) -> Result<GithubRelease, String> {
+    let url = format!("{}/{}/releases/tags/{}", GITHUB_API_BASE, repo, tag);
+    let data = fetch_github_json(&url).await?;
+
+    parse_github_release(&data)
+}
+
+/// Get available releases for any `owner/name` repository from the GitHub API
+async fn get_github_releases(repo: &str, limit: Option<u32>) -> Result<Vec<GithubRelease>, String> {
+    // GitHub pages releases, so ask for as many as the caller needs (max 100)
+    let per_page = limit.unwrap_or(30).clamp(1, 100);
+    let url = format!(
+        "{}/{}/releases?per_page={}",
+        GITHUB_API_BASE, repo, per_page
+    );
+    let data = fetch_github_json(&url).await?;
+
+    let releases_data = data
+        .as_array()
+        .ok_or("GitHub API returned no releases array")?;
+
+    let mut releases: Vec<GithubRelease> = releases_data
+        .iter()
+        .filter_map(|release| parse_github_release(release).ok())
+        .collect();
+
+    if let Some(limit) = limit {
+        releases.truncate(limit as usize);
+    }
+
     Ok(releases)
 }
 
+/// Get latest release information for an arbitrary GitHub repository
+#[tauri::command]
+pub async fn fetch_release(repo: String) -> Result<GithubRelease, String> {
+    let repo = repo.trim().trim_matches('/');
+    let is_valid = repo
+        .split_once('/')
+        .is_some_and(|(owner, name)| !owner.is_empty() && !name.is_empty() && !name.contains('/'));
+
+    if !is_valid {
+        return Err(format!(
+            "Invalid repository '{}', expected 'owner/name'",
+            repo
+        ));
+    }
+
+    get_latest_release(repo).await
+}
+
+/// Get latest release information from UNGH API
+#[tauri::command]
+pub async fn get_latest_pandoc_release() -> Result<GithubRelease, String> {
+    get_latest_release(PANDOC_REPO).await
+}
+
+/// Get all available releases
+#[tauri::command]
+pub async fn get_pandoc_releases(limit: Option<u32>) -> Result<Vec<GithubRelease>, String> {
+    get_releases(PANDOC_REPO, limit).await
+}
+
 /// Parse UNGH API response to GithubRelease
-fn parse_ungh_release(data: serde_json::Value) -> Result<GithubRelease, String> {
-    let tag_name = data["tag"].as_str().unwrap_or("").to_string();
+fn parse_ungh_release(repo: &str, data: serde_json::Value) -> Result<GithubRelease, String> {
+    let tag_name = match data["tag"].as_str() {
+        Some(tag) if !tag.is_empty() => tag.to_string(),
+        _ => return Err(unexpected_ungh_response(&data.to_string())),
+    };
 
     let name = data["name"].as_str().unwrap_or(&tag_name).to_string();
 
@@ -417,8 +605,11 @@ fn parse_ungh_release(data: serde_json::Value) -> Result<GithubRelease, String>
 
     let published_at = data["publishedAt"].as_str().unwrap_or("").to_string();
 
-    // Generate assets since UNGH doesn't provide them
-    let assets = generate_github_assets(&tag_name);
+    // Prefer the assets reported by UNGH, generating the known pandoc assets otherwise
+    let mut assets = parse_ungh_assets(&data);
+    if assets.is_empty() && repo == PANDOC_REPO {
+        assets = generate_github_assets(&tag_name);
+    }
 
     Ok(GithubRelease {
         tag_name,
@@ -429,6 +620,72 @@ fn parse_ungh_release(data: serde_json::Value) -> Result<GithubRelease, String>
     })
 }
 
+/// Parse a GitHub API release object to GithubRelease, keeping its real asset list
+fn parse_github_release(data: &serde_json::Value) -> Result<GithubRelease, String> {
+    let tag_name = match data["tag_name"].as_str() {
+        Some(tag) if !tag.is_empty() => tag.to_string(),
+        _ => return Err("GitHub API returned a release without a tag".to_string()),
+    };
+
+    let name = data["name"]
+        .as_str()
+        .filter(|name| !name.is_empty())
+        .unwrap_or(&tag_name)
+        .to_string();
+
+    let assets = data["assets"]
+        .as_array()
+        .map(|assets| {
+            assets
+                .iter()
+                .filter_map(|asset| {
+                    Some(GithubAsset {
+                        name: asset["name"].as_str()?.to_string(),
+                        download_url: asset["browser_download_url"].as_str()?.to_string(),
+                        size: asset["size"].as_u64().unwrap_or(0),
+                        content_type: asset["content_type"].as_str().unwrap_or("").to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(GithubRelease {
+        tag_name,
+        name,
+        body: data["body"].as_str().unwrap_or("").to_string(),
+        published_at: data["published_at"].as_str().unwrap_or("").to_string(),
+        assets,
+    })
+}
+
+/// Parse the asset list of a UNGH release, if present
+fn parse_ungh_assets(data: &serde_json::Value) -> Vec<GithubAsset> {
+    let Some(assets) = data["assets"].as_array() else {
+        return Vec::new();
+    };
+
+    assets
+        .iter()
+        .filter_map(|asset| {
+            let download_url = asset["downloadUrl"].as_str()?.to_string();
+            // Prioritize asset 'name' field, fallback to parsing 'downloadUrl'
+            let name = asset["name"]
+                .as_str()
+                .or_else(|| download_url.rsplit('/').next())
+                .unwrap_or("")
+                .to_string();
+
+            Some(GithubAsset {
+                name,
+                download_url,
+                size: asset["size"].as_u64().unwrap_or(0),
+                content_type: asset["contentType"].as_str().unwrap_or("").to_string(),
+            })
+        })
+        .collect()
+}
+
 /// Generate GitHub assets for a release (comprehensive list)
 fn generate_github_assets(tag: &str) -> Vec<GithubAsset> {
     let base_url = format!("https://github.com/jgm/pandoc/releases/download/{}", tag);
@@ -486,15 +743,9 @@ pub async fn get_version_info(current_version: Option<String>) -> Result<Version
     let releases = get_pandoc_releases(Some(20)).await?;
     let available_versions: Vec<String> = releases.into_iter().map(|r| r.tag_name).collect();
 
-    // Normalize versions for comparison
-    let normalized_current = current_version.as_ref().map(|v| normalize_version(v));
-    let normalized_latest = normalize_version(&latest_version);
-
-    let is_update_available = if let Some(ref current_norm) = normalized_current {
-        current_norm != &normalized_latest
-    } else {
-        true
-    };
+    let is_update_available = current_version
+        .as_ref()
+        .map_or(true, |current| is_newer_version(current, &latest_version));
 
     Ok(VersionInfo {
         current: current_version,
@@ -506,22 +757,173 @@ pub async fn get_version_info(current_version: Option<String>) -> Result<Version
 
 /// Download pandoc for current platform with improved asset selection
 #[tauri::command]
-pub async fn download_pandoc(version: String, download_dir: String) -> Result<String, String> {
-    let config = DownloadConfig::current_platform();
+pub async fn download_pandoc(
+    version: String,
+    download_dir: String,
+    app_handle: AppHandle,
+) -> Result<DownloadResult, String> {
+    let config = DownloadConfig::from_settings(&app_handle);
     download_tool(
         DownloadType::Pandoc,
         Some(version),
         PathBuf::from(download_dir),
         config,
+        Some(&app_handle),
     )
     .await
 }
 
-/// Download file with progress tracking
-async fn download_file(url: &str, dest_path: &PathBuf) -> Result<String, String> {
-    let response = reqwest::get(url)
+/// Minimum time between two `download-progress` events
+const DOWNLOAD_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Window download speed is averaged over; short enough to follow a changing connection,
+/// unlike total/elapsed which lags behind
+const DOWNLOAD_SPEED_WINDOW: Duration = Duration::from_secs(5);
+
+/// Download speed as a moving average over the last few seconds
+struct SpeedTracker {
+    /// (time, bytes downloaded so far), oldest first
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl SpeedTracker {
+    fn new() -> Self {
+        Self {
+            samples: VecDeque::from([(Instant::now(), 0)]),
+        }
+    }
+
+    /// Record the bytes downloaded so far and return the current speed in bytes per second
+    fn update(&mut self, downloaded: u64) -> f64 {
+        let now = Instant::now();
+        self.samples.push_back((now, downloaded));
+
+        // Keep one sample at or beyond the window edge as the baseline
+        while self
+            .samples
+            .get(1)
+            .is_some_and(|(time, _)| now.duration_since(*time) >= DOWNLOAD_SPEED_WINDOW)
+        {
+            self.samples.pop_front();
+        }
+
+        let Some((start, start_bytes)) = self.samples.front() else {
+            return 0.0;
+        };
+        let elapsed = now.duration_since(*start).as_secs_f64();
+        if elapsed > 0.0 {
+            downloaded.saturating_sub(*start_bytes) as f64 / elapsed
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Emit a `download-progress` event when an app handle is available
+fn emit_download_progress(
+    app_handle: Option<&AppHandle>,
+    downloaded: u64,
+    total: u64,
+    speed_bytes_per_sec: f64,
+) {
+    if let Some(app_handle) = app_handle {
+        let percentage = if total > 0 {
+            (downloaded as f64 / total as f64) * 100.0
+        } else {
+            0.0
+        };
+        let eta_seconds = (speed_bytes_per_sec > 0.0 && total > downloaded)
+            .then(|| ((total - downloaded) as f64 / speed_bytes_per_sec).ceil() as u64);
+
+        let _ = app_handle.emit(
+            "download-progress",
+            DownloadProgress {
+                downloaded,
+                total,
+                percentage,
+                speed: format!("{}/s", format_file_size(speed_bytes_per_sec as u64)),
+                speed_bytes_per_sec,
+                eta_seconds,
+            },
+        );
+    }
+}
+
+/// Downloads in progress, keyed by destination path, so they can be cancelled
+#[derive(Default)]
+pub struct DownloadRegistry {
+    cancel_senders: Mutex<HashMap<PathBuf, oneshot::Sender<()>>>,
+}
+
+/// Error returned by a download that was stopped through `cancel_all_downloads`
+const DOWNLOAD_CANCELLED_ERROR: &str = "Download cancelled";
+
+/// Cancel every download in progress; each one removes its partial file
+/// Returns the number of downloads cancelled
+#[tauri::command]
+pub fn cancel_all_downloads(app_handle: AppHandle) -> usize {
+    let Some(registry) = app_handle.try_state::<DownloadRegistry>() else {
+        return 0;
+    };
+    let Ok(mut running) = registry.cancel_senders.lock() else {
+        return 0;
+    };
+
+    let cancelled = running
+        .drain()
+        .filter(|(_, sender)| sender.send(()).is_ok())
+        .count();
+    tracing::info!(cancelled, "Cancelled all downloads");
+    cancelled
+}
+
+/// Register a download so `cancel_all_downloads` can stop it
+fn register_download(
+    app_handle: Option<&AppHandle>,
+    dest_path: &Path,
+) -> Option<oneshot::Receiver<()>> {
+    let registry = app_handle?.try_state::<DownloadRegistry>()?;
+    let (cancel_tx, cancel_rx) = oneshot::channel();
+    registry
+        .cancel_senders
+        .lock()
+        .ok()?
+        .insert(dest_path.to_path_buf(), cancel_tx);
+    Some(cancel_rx)
+}
+
+/// Remove a download from the registry once it has finished
+fn unregister_download(app_handle: Option<&AppHandle>, dest_path: &Path) {
+    if let Some(registry) = app_handle.and_then(|handle| handle.try_state::<DownloadRegistry>()) {
+        if let Ok(mut running) = registry.cancel_senders.lock() {
+            running.remove(dest_path);
+        }
+    }
+}
+
+/// Download file with progress tracking, returning the number of bytes written
+/// The bytes on disk must be the archive itself: a mirror adding `Content-Encoding: gzip`
+/// on top of a .tar.gz would otherwise be transparently decoded into a bare tarball
+async fn download_file(
+    url: &str,
+    dest_path: &PathBuf,
+    timeout: Duration,
+    app_handle: Option<&AppHandle>,
+) -> Result<u64, String> {
+    let client = crate::network::client_builder()
+        .no_gzip()
+        .no_deflate()
+        .no_brotli()
+        .build()
+        .map_err(|e| format!("Failed to create download client: {}", e))?;
+
+    let mut response = client
+        .get(url)
+        .header(reqwest::header::ACCEPT_ENCODING, "identity")
+        .timeout(timeout)
+        .send()
         .await
-        .map_err(|e| format!("Failed to start download: {}", e))?;
+        .map_err(|e| format!("Failed to start download: {}", describe_request_error(&e)))?;
 
     if !response.status().is_success() {
         return Err(format!(
@@ -530,7 +932,7 @@ async fn download_file(url: &str, dest_path: &PathBuf) -> Result<String, String>
         ));
     }
 
-    let _total_size = response.content_length().unwrap_or(0);
+    let total_size = response.content_length().unwrap_or(0);
 
     // Create destination directory if it doesn't exist
     if let Some(parent) = dest_path.parent() {
@@ -541,24 +943,102 @@ async fn download_file(url: &str, dest_path: &PathBuf) -> Result<String, String>
     let mut file =
         std::fs::File::create(dest_path).map_err(|e| format!("Failed to create file: {}", e))?;
 
-    let bytes = response
-        .bytes()
-        .await
-        .map_err(|e| format!("Failed to read response bytes: {}", e))?;
+    // A dropped sender means the download was replaced, not cancelled
+    let cancel_rx = register_download(app_handle, dest_path);
+    let mut cancelled = std::pin::pin!(async move {
+        let cancelled = match cancel_rx {
+            Some(cancel_rx) => cancel_rx.await.is_ok(),
+            None => false,
+        };
+        if !cancelled {
+            std::future::pending::<()>().await;
+        }
+    });
+
+    // Write chunks as they arrive so progress can be reported
+    let mut downloaded = 0u64;
+    let mut speed = SpeedTracker::new();
+    let mut last_emitted = Instant::now();
+    let streamed = async {
+        loop {
+            let chunk = tokio::select! {
+                _ = &mut cancelled => return Err(DOWNLOAD_CANCELLED_ERROR.to_string()),
+                chunk = response.chunk() => chunk.map_err(|e| {
+                    format!(
+                        "Failed to read response bytes: {}",
+                        describe_request_error(&e)
+                    )
+                })?,
+            };
+            let Some(chunk) = chunk else {
+                return Ok(());
+            };
 
-    file.write_all(&bytes)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
+            file.write_all(&chunk)
+                .map_err(|e| format!("Failed to write file: {}", e))?;
+            downloaded += chunk.len() as u64;
 
-    println!(
-        "Downloaded {} ({})",
-        dest_path.display(),
-        format_file_size(bytes.len() as u64)
+            if last_emitted.elapsed() >= DOWNLOAD_PROGRESS_INTERVAL {
+                last_emitted = Instant::now();
+                let bytes_per_sec = speed.update(downloaded);
+                emit_download_progress(app_handle, downloaded, total_size, bytes_per_sec);
+            }
+        }
+    }
+    .await;
+    unregister_download(app_handle, dest_path);
+
+    if let Err(e) = streamed {
+        if e == DOWNLOAD_CANCELLED_ERROR {
+            // Don't leave a partial archive behind
+            drop(file);
+            let _ = std::fs::remove_file(dest_path);
+            tracing::info!(path = %dest_path.display(), "Download cancelled");
+        }
+        return Err(e);
+    }
+
+    let bytes_per_sec = speed.update(downloaded);
+    emit_download_progress(
+        app_handle,
+        downloaded,
+        total_size.max(downloaded),
+        bytes_per_sec,
+    );
+
+    tracing::info!(
+        path = %dest_path.display(),
+        size = %format_file_size(downloaded),
+        "Download complete"
     );
 
     file.flush()
         .map_err(|e| format!("Failed to flush file: {}", e))?;
 
-    Ok(dest_path.to_string_lossy().to_string())
+    Ok(downloaded)
+}
+
+/// Name the kind of failure (timeout, DNS/TLS/connection) in front of a request error
+fn describe_request_error(error: &reqwest::Error) -> String {
+    if error.is_timeout() {
+        format!("timed out ({})", error)
+    } else if error.is_connect() {
+        format!("connection failed, check DNS/TLS/proxy ({})", error)
+    } else {
+        error.to_string()
+    }
+}
+
+/// Check a downloaded file against the size the release reports
+/// Broken mirrors may answer 200 with an error page or a truncated file
+fn verify_download_size(asset: &GithubAsset, bytes: u64) -> Result<(), String> {
+    if asset.size > 0 && bytes != asset.size {
+        return Err(format!(
+            "Downloaded file is corrupt or incomplete: expected {} bytes, got {}",
+            asset.size, bytes
+        ));
+    }
+    Ok(())
 }
 
 /// Extract downloaded archive
@@ -566,39 +1046,191 @@ async fn download_file(url: &str, dest_path: &PathBuf) -> Result<String, String>
 pub async fn extract_pandoc_archive(
     archive_path: String,
     extract_dir: String,
+    app_handle: AppHandle,
 ) -> Result<String, String> {
-    extract_archive_unified(PathBuf::from(archive_path), PathBuf::from(extract_dir)).await
+    extract_archive_unified(
+        PathBuf::from(archive_path),
+        PathBuf::from(extract_dir),
+        Some(&app_handle),
+    )
+    .await
 }
 
-/// Extract ZIP archive
-fn extract_zip(archive_path: &PathBuf, extract_dir: &PathBuf) -> Result<String, String> {
-    let file =
-        std::fs::File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+/// Archive extensions produced by pandoc and typst releases
+const ARCHIVE_EXTENSIONS: &[&str] = &[".zip", ".tar.gz", ".tgz", ".tar.xz", ".tar.bz2", ".7z"];
 
-    let mut archive =
-        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read ZIP archive: {}", e))?;
+/// Check whether a file name looks like a downloaded release archive
+fn is_archive_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    ARCHIVE_EXTENSIONS.iter().any(|ext| name.ends_with(ext))
+}
 
-    for i in 0..archive.len() {
-        let mut file = archive
-            .by_index(i)
-            .map_err(|e| format!("Failed to read file from archive: {}", e))?;
+/// Detect the tool and version from a release archive name
+/// e.g. "pandoc-3.7.0.2-linux-amd64.tar.gz" is pandoc 3.7.0.2; typst archives carry no version
+fn parse_archive_name(name: &str) -> (Option<String>, Option<String>) {
+    let lower = name.to_lowercase();
+    if let Some(rest) = lower.strip_prefix("pandoc-") {
+        let version = rest
+            .split('-')
+            .next()
+            .filter(|v| v.starts_with(|c: char| c.is_ascii_digit()))
+            .map(str::to_string);
+        return (Some("pandoc".to_string()), version);
+    }
+    if lower.starts_with("typst-") {
+        return (Some("typst".to_string()), None);
+    }
 
-        let outpath = extract_dir.join(file.name());
+    (None, None)
+}
 
-        if file.name().ends_with('/') {
-            std::fs::create_dir_all(&outpath)
-                .map_err(|e| format!("Failed to create directory: {}", e))?;
-        } else {
-            if let Some(p) = outpath.parent() {
-                std::fs::create_dir_all(p)
-                    .map_err(|e| format!("Failed to create parent directory: {}", e))?;
+/// List the release archives in a download directory, sorted by name
+#[tauri::command]
+pub async fn list_downloaded_archives(dir: String) -> Vec<ArchiveInfo> {
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut archives: Vec<ArchiveInfo> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            if !is_archive_name(&name) {
+                return None;
             }
 
-            let mut outfile = std::fs::File::create(&outpath)
-                .map_err(|e| format!("Failed to create extracted file: {}", e))?;
+            let (tool, version) = parse_archive_name(&name);
+            Some(ArchiveInfo {
+                path: entry.path().to_string_lossy().to_string(),
+                size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                name,
+                tool,
+                version,
+            })
+        })
+        .collect();
+
+    archives.sort_by(|a, b| a.name.cmp(&b.name));
+    archives
+}
 
-            std::io::copy(&mut file, &mut outfile)
-                .map_err(|e| format!("Failed to extract file: {}", e))?;
+/// Delete a downloaded release archive; other files are refused
+#[tauri::command]
+pub async fn delete_archive(path: String) -> Result<(), String> {
+    let archive = Path::new(&path);
+    let is_archive = archive
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(is_archive_name);
+
+    if !is_archive || !archive.is_file() {
+        return Err(format!("Not a downloaded archive: {}", path));
+    }
+
+    std::fs::remove_file(archive).map_err(|e| format!("Failed to delete archive: {}", e))
+}
+
+/// Minimum number of bytes between two byte-based `extract-progress` events
+const EXTRACT_PROGRESS_STEP: u64 = 1024 * 1024;
+
+/// Emit an `extract-progress` event when an app handle is available
+fn emit_extract_progress(app_handle: Option<&AppHandle>, current: u64, total: u64, unit: &str) {
+    if let Some(app_handle) = app_handle {
+        let percentage = if total > 0 {
+            (current as f64 / total as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        let _ = app_handle.emit(
+            "extract-progress",
+            ExtractProgress {
+                current,
+                total,
+                percentage,
+                unit: unit.to_string(),
+            },
+        );
+    }
+}
+
+/// Reader wrapper reporting how many archive bytes have been consumed
+struct ProgressReader<'a, R: Read> {
+    inner: R,
+    read: u64,
+    total: u64,
+    last_emitted: u64,
+    app_handle: Option<&'a AppHandle>,
+}
+
+impl<'a, R: Read> ProgressReader<'a, R> {
+    fn new(inner: R, total: u64, app_handle: Option<&'a AppHandle>) -> Self {
+        Self {
+            inner,
+            read: 0,
+            total,
+            last_emitted: 0,
+            app_handle,
+        }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read += n as u64;
+
+        if self.read - self.last_emitted >= EXTRACT_PROGRESS_STEP || (n == 0 && self.read > 0) {
+            self.last_emitted = self.read;
+            emit_extract_progress(self.app_handle, self.read, self.total, "bytes");
+        }
+
+        Ok(n)
+    }
+}
+
+/// Extract ZIP archive
+fn extract_zip(
+    archive_path: &PathBuf,
+    extract_dir: &PathBuf,
+    app_handle: Option<&AppHandle>,
+) -> Result<String, String> {
+    let file =
+        std::fs::File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read ZIP archive: {}", e))?;
+
+    let canonical_extract_dir = extract_dir
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve extract directory: {}", e))?;
+
+    let total = archive.len() as u64;
+
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read file from archive: {}", e))?;
+
+        let outpath = sanitize_archive_entry_path(extract_dir, file.name())?;
+
+        if file.name().ends_with('/') {
+            std::fs::create_dir_all(&outpath)
+                .map_err(|e| format!("Failed to create directory: {}", e))?;
+            ensure_within_extract_dir(&canonical_extract_dir, &outpath, file.name())?;
+        } else {
+            if let Some(p) = outpath.parent() {
+                std::fs::create_dir_all(p)
+                    .map_err(|e| format!("Failed to create parent directory: {}", e))?;
+                ensure_within_extract_dir(&canonical_extract_dir, p, file.name())?;
+            }
+
+            let mut outfile = std::fs::File::create(&outpath)
+                .map_err(|e| format!("Failed to create extracted file: {}", e))?;
+
+            std::io::copy(&mut file, &mut outfile)
+                .map_err(|e| format!("Failed to extract file: {}", e))?;
         }
 
         // Set permissions on Unix
@@ -610,20 +1242,68 @@ fn extract_zip(archive_path: &PathBuf, extract_dir: &PathBuf) -> Result<String,
                 // Ignore permission errors
             }
         }
+
+        emit_extract_progress(app_handle, i as u64 + 1, total, "files");
     }
 
     Ok(extract_dir.to_string_lossy().to_string())
 }
 
+/// Resolve an archive entry name against the extraction directory (zip-slip protection)
+/// Rejects absolute paths, drive prefixes and any `..` component
+fn sanitize_archive_entry_path(extract_dir: &PathBuf, entry_name: &str) -> Result<PathBuf, String> {
+    let entry_path = Path::new(entry_name);
+
+    for component in entry_path.components() {
+        match component {
+            Component::Normal(_) | Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(format!(
+                    "Refusing to extract unsafe archive entry: {}",
+                    entry_name
+                ));
+            }
+        }
+    }
+
+    Ok(extract_dir.join(entry_path))
+}
+
+/// Verify that an existing path resolves inside the canonicalized extraction directory
+fn ensure_within_extract_dir(
+    canonical_extract_dir: &Path,
+    path: &Path,
+    entry_name: &str,
+) -> Result<(), String> {
+    let canonical_path = path
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve extracted path: {}", e))?;
+
+    if canonical_path.starts_with(canonical_extract_dir) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Archive entry escapes extraction directory: {}",
+            entry_name
+        ))
+    }
+}
+
 /// Extract TAR.GZ archive
-fn extract_tar_gz(archive_path: &PathBuf, extract_dir: &PathBuf) -> Result<String, String> {
+/// The entry count is unknown upfront, so progress is reported in compressed bytes read
+fn extract_tar_gz(
+    archive_path: &PathBuf,
+    extract_dir: &PathBuf,
+    app_handle: Option<&AppHandle>,
+) -> Result<String, String> {
     use flate2::read::GzDecoder;
     use tar::Archive;
 
     let file =
         std::fs::File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let total = file.metadata().map(|m| m.len()).unwrap_or(0);
 
-    let gz = GzDecoder::new(file);
+    let gz = GzDecoder::new(ProgressReader::new(file, total, app_handle));
     let mut archive = Archive::new(gz);
 
     archive
@@ -636,22 +1316,35 @@ fn extract_tar_gz(archive_path: &PathBuf, extract_dir: &PathBuf) -> Result<Strin
 /// Discover all available pandoc sources
 #[tauri::command]
 pub async fn discover_pandoc_sources(app_handle: AppHandle) -> Vec<PandocManager> {
+    let preference = crate::config::load_config(&app_handle).source_preference;
     let mut sources = Vec::new();
 
     // 1. Check bundled pandoc (highest priority after custom)
-    let bundled_source = PandocManager::new(PandocSource::Managed);
-    sources.push(bundled_source);
+    if preference.allows_managed() {
+        let bundled_source = PandocManager::new(PandocSource::Managed);
+        sources.push(bundled_source);
+    }
 
     // 2. Discover system pandoc installations
-    let system_paths = crate::utils::get_search_paths();
-    for path_str in system_paths {
-        let path = PathBuf::from(&path_str);
-        if path.exists() && is_executable(&path) {
-            let system_source = PandocManager::new(PandocSource::System(path));
-            sources.push(system_source);
+    if preference.allows_system() {
+        let system_paths = crate::utils::get_search_paths();
+        for path_str in system_paths {
+            let path = PathBuf::from(&path_str);
+            let reachable =
+                crate::utils::path_exists_with_timeout(&path, crate::utils::PATH_CHECK_TIMEOUT);
+            if reachable && is_executable(&path) {
+                let system_source = PandocManager::new(PandocSource::System(path));
+                sources.push(system_source);
+            }
         }
     }
 
+    // Honor a preference for the user's own install by moving managed behind it
+    if preference.prefers_system() && preference.allows_managed() {
+        let managed = sources.remove(0);
+        sources.push(managed);
+    }
+
     // Validate each source
     for source in &mut sources {
         let _ = source.validate(&app_handle).await;
@@ -677,7 +1370,7 @@ fn is_executable(path: &PathBuf) -> bool {
     {
         // On Windows, check if it's a .exe file or if it can be executed
         path.extension().map_or(false, |ext| ext == "exe")
-            || crate::utils::create_hidden_command(&path.to_string_lossy())
+            || crate::utils::create_hidden_command(path)
                 .arg("--version")
                 .output()
                 .map(|output| output.status.success())
@@ -711,6 +1404,13 @@ pub async fn get_best_pandoc_manager(app_handle: AppHandle) -> Option<PandocMana
 
     for mut source in sources {
         if let Ok(()) = source.validate(&app_handle).await {
+            // Remember the winner so conversions don't repeat the search
+            if let Some(path) = source.get_executable_path(&app_handle) {
+                crate::pandoc::set_cached_pandoc_path(
+                    &app_handle,
+                    Some(path.to_string_lossy().to_string()),
+                );
+            }
             return Some(source);
         }
     }
@@ -718,53 +1418,149 @@ pub async fn get_best_pandoc_manager(app_handle: AppHandle) -> Option<PandocMana
     None
 }
 
+/// Get the version of every managed, system and configured custom pandoc
+#[tauri::command]
+pub async fn get_all_pandoc_versions(app_handle: AppHandle) -> Vec<SourceVersion> {
+    let config = crate::config::load_config(&app_handle);
+    let mut sources = discover_pandoc_sources(app_handle.clone()).await;
+
+    // A configured custom path takes priority over everything discovered
+    if let Some(custom_path) = config.custom_path.filter(|p| !p.is_empty()) {
+        let mut custom = PandocManager::new(PandocSource::Custom(PathBuf::from(custom_path)));
+        let _ = custom.validate(&app_handle).await;
+        if config.use_custom_path {
+            sources.insert(0, custom);
+        } else {
+            sources.push(custom);
+        }
+    }
+
+    // Same choice as get_best_pandoc_manager (plus the custom override)
+    let active_index = sources.iter().position(|source| {
+        source.available
+            && (config.use_custom_path || !matches!(source.source, PandocSource::Custom(_)))
+    });
+
+    let newest = sources
+        .iter()
+        .filter_map(|source| source.get_info().map(|info| info.version.as_str()))
+        .fold(None, |newest: Option<&str>, version| match newest {
+            Some(best) if !is_newer_version(best, version) => Some(best),
+            _ => Some(version),
+        })
+        .map(str::to_string);
+
+    sources
+        .iter()
+        .enumerate()
+        .map(|(index, source)| {
+            let version = source.get_info().map(|info| info.version.clone());
+            let is_outdated = match (&version, &newest) {
+                (Some(version), Some(newest)) => is_newer_version(version, newest),
+                _ => false,
+            };
+
+            SourceVersion {
+                source: source.source.clone(),
+                path: source
+                    .get_executable_path(&app_handle)
+                    .map(|p| p.to_string_lossy().to_string()),
+                version,
+                is_active: active_index == Some(index),
+                is_outdated,
+            }
+        })
+        .collect()
+}
+
 /// Update bundled pandoc by downloading latest version (legacy function)
 #[tauri::command]
 pub async fn update_bundled_pandoc(app_handle: AppHandle) -> Result<String, String> {
     update_managed_pandoc(app_handle).await
 }
 
-/// Check if bundled pandoc needs update
-#[tauri::command]
-pub async fn check_bundled_pandoc_update(app_handle: AppHandle) -> Result<bool, String> {
-    // Get current bundled pandoc version
+/// Get the version of the managed pandoc, None if it isn't installed
+async fn get_managed_pandoc_version(app_handle: &AppHandle) -> Option<String> {
     let mut bundled_manager = PandocManager::new(PandocSource::Managed);
-    let current_version = if bundled_manager.validate(&app_handle).await.is_ok() {
+    bundled_manager.validate(app_handle).await.ok()?;
+    Some(
         bundled_manager
             .get_info()
             .map(|info| info.version.clone())
-            .unwrap_or_else(|| "unknown".to_string())
-    } else {
-        "none".to_string()
-    };
+            .unwrap_or_else(|| "unknown".to_string()),
+    )
+}
+
+/// Check if bundled pandoc needs update
+#[tauri::command]
+pub async fn check_bundled_pandoc_update(app_handle: AppHandle) -> Result<bool, String> {
+    // Get current bundled pandoc version
+    let current_version = get_managed_pandoc_version(&app_handle)
+        .await
+        .unwrap_or_else(|| "none".to_string());
 
     // Get latest version
     let latest_release = get_latest_pandoc_release().await?;
-    let latest_version = extract_clean_version(&latest_release.tag_name);
-    let current_clean = extract_clean_version(&current_version);
+    Ok(is_newer_version(&current_version, &latest_release.tag_name))
+}
 
-    // Simple version comparison
-    Ok(current_clean != latest_version)
+/// Check if bundled typst needs update
+#[tauri::command]
+pub async fn check_bundled_typst_update(app_handle: AppHandle) -> Result<bool, String> {
+    let current_version =
+        crate::pandoc::get_typst_version(&app_handle).unwrap_or_else(|| "none".to_string());
+
+    let latest_release = get_latest_typst_release().await?;
+    Ok(is_newer_version(&current_version, &latest_release.tag_name))
 }
 
-/// Extract clean version number for comparison
-fn extract_clean_version(version: &str) -> String {
-    version
-        .trim_start_matches('v')
-        .trim()
-        .split_whitespace()
-        .next()
-        .unwrap_or(version)
-        .to_string()
+/// Combine an installed version with the latest release; an unreachable release
+/// service leaves `latest` empty rather than failing
+fn tool_version(current: Option<String>, latest: Result<GithubRelease, String>) -> ToolVersion {
+    let latest = match latest {
+        Ok(release) => Some(release.tag_name),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to fetch latest release");
+            None
+        }
+    };
+    let update_available = latest
+        .as_deref()
+        .is_some_and(|latest| is_newer_version(current.as_deref().unwrap_or("none"), latest));
+
+    ToolVersion {
+        current,
+        latest,
+        update_available,
+    }
+}
+
+/// Get the app version together with the managed pandoc and typst versions,
+/// each with whether an update is available
+#[tauri::command]
+pub async fn get_app_tool_versions(app_handle: AppHandle) -> AppToolVersions {
+    let pandoc_version = get_managed_pandoc_version(&app_handle).await;
+    let typst_version = crate::pandoc::get_typst_version(&app_handle);
+
+    let (latest_pandoc, latest_typst) =
+        tokio::join!(get_latest_pandoc_release(), get_latest_typst_release());
+
+    AppToolVersions {
+        app_version: app_handle.package_info().version.to_string(),
+        pandoc: tool_version(pandoc_version, latest_pandoc),
+        typst: tool_version(typst_version, latest_typst),
+    }
 }
 
 /// Unified download interface for different tools
+/// Progress is emitted as `download-progress` events when an app handle is given
 pub async fn download_tool(
     download_type: DownloadType,
     version: Option<String>,
     target_dir: PathBuf,
     config: DownloadConfig,
-) -> Result<String, String> {
+    app_handle: Option<&AppHandle>,
+) -> Result<DownloadResult, String> {
     match download_type {
         DownloadType::Pandoc => {
             let version = if let Some(v) = version {
@@ -773,7 +1569,7 @@ pub async fn download_tool(
                 let latest = get_latest_pandoc_release().await?;
                 latest.tag_name
             };
-            download_pandoc_internal(version, target_dir, config).await
+            download_pandoc_internal(version, target_dir, config, app_handle).await
         }
         DownloadType::Typst => {
             let version = if let Some(v) = version {
@@ -782,7 +1578,7 @@ pub async fn download_tool(
                 let latest = get_latest_typst_release().await?;
                 latest.tag_name
             };
-            download_typst_internal(version, target_dir, config).await
+            download_typst_internal(version, target_dir, config, app_handle).await
         }
     }
 }
@@ -792,7 +1588,8 @@ async fn download_pandoc_internal(
     version: String,
     download_dir: PathBuf,
     config: DownloadConfig,
-) -> Result<String, String> {
+    app_handle: Option<&AppHandle>,
+) -> Result<DownloadResult, String> {
     let releases = get_pandoc_releases(Some(50)).await?;
 
     let release = releases
@@ -825,75 +1622,26 @@ async fn download_pandoc_internal(
 
     let download_path = download_dir.join(&asset.name);
 
-    // Try different mirrors if enabled
-    if config.use_mirrors {
-        for mirror in DOWNLOAD_MIRRORS {
-            let download_url = construct_mirror_url(mirror, &asset.download_url);
-            println!(
-                "Trying to download {} from mirror: {}",
-                asset.name,
-                if mirror.is_empty() { "GitHub" } else { mirror }
-            );
-
-            match download_file(&download_url, &download_path).await {
-                Ok(path) => return Ok(path),
-                Err(e) => {
-                    println!(
-                        "Mirror {} failed: {}",
-                        if mirror.is_empty() { "GitHub" } else { mirror },
-                        e
-                    );
-                    continue;
-                }
-            }
-        }
-        Err("All download mirrors failed".to_string())
-    } else {
-        download_file(&asset.download_url, &download_path).await
-    }
+    download_asset(asset, &download_path, &config, app_handle).await
 }
 
 /// Get latest Typst release information
 async fn get_latest_typst_release() -> Result<GithubRelease, String> {
-    // Use UNGH API for Typst (same as Pandoc)
-    let url = format!("{}/{}/releases/latest", UNGH_API_BASE, TYPST_REPO);
-
-    let response = reqwest::get(&url)
-        .await
-        .map_err(|e| format!("Failed to fetch Typst release info: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "UNGH API request failed with status: {}",
-            response.status()
-        ));
-    }
-
-    let response_text = response
-        .text()
-        .await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
-
-    let api_response: serde_json::Value =
-        serde_json::from_str(&response_text).map_err(|e| format!("Failed to parse JSON: {}", e))?;
-
-    // UNGH wraps the release in a "release" field
-    let release_data = api_response["release"].clone();
-
-    if release_data.is_null() {
-        return Err("No release data found in response".to_string());
-    }
-
-    parse_ungh_release(release_data)
+    get_latest_release(TYPST_REPO).await
 }
 
 /// Internal typst download function
 async fn download_typst_internal(
-    _version: String,
+    version: String,
     download_dir: PathBuf,
     config: DownloadConfig,
-) -> Result<String, String> {
-    let release = get_latest_typst_release().await?;
+    app_handle: Option<&AppHandle>,
+) -> Result<DownloadResult, String> {
+    // Typst tags carry a leading 'v', accept the version with or without it
+    let tag = format!("v{}", strip_version_prefix(&version));
+    let release = get_release_by_tag(TYPST_REPO, &tag)
+        .await
+        .map_err(|e| format!("Typst version {} not found: {}", version, e))?;
 
     // Find the appropriate asset for the target platform
     let asset_pattern = get_typst_asset_pattern(&config.target_os, &config.target_arch);
@@ -913,34 +1661,207 @@ async fn download_typst_internal(
 
     let download_path = download_dir.join(&asset.name);
 
-    // Try different mirrors if enabled (GitHub mirrors also work for other repos)
-    if config.use_mirrors {
-        for mirror in DOWNLOAD_MIRRORS {
-            let download_url = construct_mirror_url(mirror, &asset.download_url);
-            println!(
-                "Trying to download {} from mirror: {}",
-                asset.name,
-                if mirror.is_empty() { "GitHub" } else { mirror }
-            );
+    // GitHub mirrors also work for other repos
+    let mut result = download_asset(asset, &download_path, &config, app_handle).await?;
+    tracing::info!(version = %release.tag_name, path = %result.path, "Downloaded Typst");
+    result.version = Some(release.tag_name);
+    Ok(result)
+}
 
-            match download_file(&download_url, &download_path).await {
-                Ok(path) => return Ok(path),
-                Err(e) => {
-                    println!(
-                        "Mirror {} failed: {}",
-                        if mirror.is_empty() { "GitHub" } else { mirror },
-                        e
-                    );
-                    continue;
-                }
+/// Download a release asset, trying mirrors if enabled
+async fn download_asset(
+    asset: &GithubAsset,
+    download_path: &PathBuf,
+    config: &DownloadConfig,
+    app_handle: Option<&AppHandle>,
+) -> Result<DownloadResult, String> {
+    let started = Instant::now();
+    let download_result = |bytes: u64, mirror: &str, attempts: u32| DownloadResult {
+        path: download_path.to_string_lossy().to_string(),
+        bytes,
+        duration_ms: started.elapsed().as_millis() as u64,
+        mirror_used: mirror_label(mirror).to_string(),
+        attempts,
+        version: None,
+    };
+
+    let try_download = |url: String, timeout: Duration| async move {
+        let bytes = download_file(&url, download_path, timeout, app_handle).await?;
+        verify_download_size(asset, bytes)?;
+        Ok::<u64, String>(bytes)
+    };
+
+    // Proxy mirrors first; direct GitHub is the final attempt below unless already tried
+    let mirrors: Vec<&str> = if !config.use_mirrors {
+        Vec::new()
+    } else if config.race_mirrors {
+        // In race mode the first mirror to answer the probe is tried first, and direct
+        // GitHub competes with the proxies
+        rank_mirrors(&asset.download_url).await
+    } else {
+        DOWNLOAD_MIRRORS
+            .iter()
+            .copied()
+            .filter(|mirror| !mirror.is_empty())
+            .collect()
+    };
+
+    let mut attempts = 0;
+    let mut failures = Vec::new();
+    let mut tried_direct = false;
+    for mirror in mirrors {
+        attempts += 1;
+        tried_direct |= mirror.is_empty();
+        let download_url = construct_mirror_url(mirror, &asset.download_url);
+        let timeout = if mirror.is_empty() {
+            DIRECT_DOWNLOAD_TIMEOUT
+        } else {
+            MIRROR_DOWNLOAD_TIMEOUT
+        };
+        tracing::info!(
+            asset = %asset.name,
+            mirror = mirror_label(mirror),
+            "Trying download mirror"
+        );
+
+        match try_download(download_url, timeout).await {
+            Ok(bytes) => return Ok(download_result(bytes, mirror, attempts)),
+            // A cancelled download shouldn't fall through to the next mirror
+            Err(e) if e == DOWNLOAD_CANCELLED_ERROR => return Err(e),
+            Err(e) => {
+                tracing::warn!(mirror = mirror_label(mirror), error = %e, "Mirror download failed");
+                failures.push(format!("{}: {}", mirror_label(mirror), e));
             }
         }
-        Err("All download mirrors failed".to_string())
+    }
+
+    // A clean direct download with a longer timeout, in case only the mirrors are broken
+    if !tried_direct {
+        attempts += 1;
+        tracing::info!(asset = %asset.name, "Trying direct GitHub download");
+        match try_download(asset.download_url.clone(), DIRECT_DOWNLOAD_TIMEOUT).await {
+            Ok(bytes) => return Ok(download_result(bytes, "", attempts)),
+            Err(e) => {
+                tracing::warn!(error = %e, "Direct GitHub download failed");
+                failures.push(format!("{}: {}", mirror_label(""), e));
+            }
+        }
+    }
+
+    // Don't leave a corrupt archive behind for a later extraction
+    let _ = std::fs::remove_file(download_path);
+    Err(format!(
+        "All download mirrors failed:\n{}",
+        failures.join("\n")
+    ))
+}
+
+/// Probe all mirrors concurrently and return as soon as one responds with a good status
+/// The first responder leads; mirrors whose probes were still pending follow as fallbacks
+/// for a download that fails midway
+async fn rank_mirrors(original_url: &str) -> Vec<&'static str> {
+    let client = crate::network::http_client();
+
+    let mut probes: FuturesUnordered<_> = DOWNLOAD_MIRRORS
+        .iter()
+        .map(|mirror| probe_mirror(&client, mirror, original_url))
+        .collect();
+
+    let mut failed = Vec::new();
+    while let Some((mirror, latency)) = probes.next().await {
+        if latency.is_none() {
+            failed.push(mirror);
+            continue;
+        }
+
+        // Dropping the remaining probes cancels them
+        let mut ranked = vec![mirror];
+        ranked.extend(
+            DOWNLOAD_MIRRORS
+                .iter()
+                .copied()
+                .filter(|other| *other != mirror && !failed.contains(other)),
+        );
+        return ranked;
+    }
+
+    Vec::new()
+}
+
+/// Send a HEAD request through every mirror concurrently
+/// Returns each mirror with its latency (None if unreachable), fastest first
+async fn probe_mirrors(original_url: &str) -> Vec<(&'static str, Option<u64>)> {
+    let client = crate::network::http_client();
+
+    let mut probes: FuturesUnordered<_> = DOWNLOAD_MIRRORS
+        .iter()
+        .map(|mirror| probe_mirror(&client, mirror, original_url))
+        .collect();
+
+    let mut results = Vec::new();
+    while let Some(result) = probes.next().await {
+        results.push(result);
+    }
+
+    // Reachable mirrors by latency, unreachable ones last
+    results.sort_by_key(|(_, latency)| latency.unwrap_or(u64::MAX));
+    results
+}
+
+/// Send a HEAD request through one mirror, returning its latency (None if unreachable)
+async fn probe_mirror(
+    client: &reqwest::Client,
+    mirror: &'static str,
+    original_url: &str,
+) -> (&'static str, Option<u64>) {
+    let started = Instant::now();
+    let result = client
+        .head(construct_mirror_url(mirror, original_url))
+        .timeout(MIRROR_PROBE_TIMEOUT)
+        .send()
+        .await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(response) if response.status().is_success() => (mirror, Some(latency_ms)),
+        Ok(response) => {
+            tracing::warn!(
+                mirror = mirror_label(mirror),
+                status = %response.status(),
+                "Mirror probe returned an error status"
+            );
+            (mirror, None)
+        }
+        Err(e) => {
+            tracing::warn!(mirror = mirror_label(mirror), error = %e, "Mirror probe failed");
+            (mirror, None)
+        }
+    }
+}
+
+/// Human-readable name of a mirror (the empty prefix is direct GitHub)
+fn mirror_label(mirror: &str) -> &str {
+    if mirror.is_empty() {
+        "GitHub"
     } else {
-        download_file(&asset.download_url, &download_path).await
+        mirror
     }
 }
 
+/// Check which download mirrors are currently reachable, fastest first
+#[tauri::command]
+pub async fn test_mirrors() -> Vec<MirrorStatus> {
+    probe_mirrors(MIRROR_TEST_URL)
+        .await
+        .into_iter()
+        .map(|(mirror, latency_ms)| MirrorStatus {
+            mirror: mirror_label(mirror).to_string(),
+            reachable: latency_ms.is_some(),
+            latency_ms,
+        })
+        .collect()
+}
+
 /// Get pandoc asset patterns for specific platform
 fn get_pandoc_asset_patterns_for_platform(target_os: &str, target_arch: &str) -> Vec<String> {
     // Based on pandoc release assets like 'pandoc-3.7.0.2-x86_64-macOS.zip'
@@ -976,6 +1897,7 @@ fn get_typst_asset_pattern(target_os: &str, target_arch: &str) -> String {
 pub async fn extract_archive_unified(
     archive_path: PathBuf,
     extract_dir: PathBuf,
+    app_handle: Option<&AppHandle>,
 ) -> Result<String, String> {
     // Create extraction directory
     std::fs::create_dir_all(&extract_dir)
@@ -986,8 +1908,8 @@ pub async fn extract_archive_unified(
         .and_then(|e| e.to_str())
         .unwrap_or("");
 
-    match extension {
-        "zip" => extract_zip(&archive_path, &extract_dir),
+    let result = match extension {
+        "zip" => extract_zip(&archive_path, &extract_dir, app_handle),
         "gz" => {
             // Handle .tar.gz and .tar.xz
             let file_name = archive_path
@@ -995,7 +1917,7 @@ pub async fn extract_archive_unified(
                 .and_then(|n| n.to_str())
                 .unwrap_or("");
             if file_name.contains(".tar.") {
-                extract_tar_gz(&archive_path, &extract_dir)
+                extract_tar_gz(&archive_path, &extract_dir, app_handle)
             } else {
                 Err(format!("Unsupported .gz format: {}", file_name))
             }
@@ -1006,22 +1928,90 @@ pub async fn extract_archive_unified(
                 .and_then(|n| n.to_str())
                 .unwrap_or("");
             if file_name.contains(".tar.") {
-                extract_tar_xz(&archive_path, &extract_dir)
+                extract_tar_xz(&archive_path, &extract_dir, app_handle)
             } else {
                 Err(format!("Unsupported .xz format: {}", file_name))
             }
         }
+        "bz2" => {
+            let file_name = archive_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("");
+            if file_name.contains(".tar.") {
+                extract_tar_bz2(&archive_path, &extract_dir, app_handle)
+            } else {
+                Err(format!("Unsupported .bz2 format: {}", file_name))
+            }
+        }
+        "7z" => extract_7z(&archive_path, &extract_dir, app_handle),
         _ => Err(format!("Unsupported archive format: {}", extension)),
+    };
+
+    // Extracted files inherit the archive's quarantine, which stops them from running
+    if result.is_ok() && cfg!(target_os = "macos") {
+        if let Err(e) = crate::utils::clear_quarantine_attr(&extract_dir) {
+            tracing::warn!(error = %e, "Failed to clear quarantine after extraction");
+        }
+    }
+
+    // Not every archive format keeps the execute bit (7z doesn't)
+    if result.is_ok() && cfg!(unix) {
+        make_tool_binaries_executable(&extract_dir);
+    }
+
+    result
+}
+
+/// Executables shipped in pandoc and typst release archives
+const TOOL_BINARY_NAMES: &[&str] = &["pandoc", "pandoc-lua", "pandoc-server", "typst"];
+
+/// Set the execute bit on the tool binaries under an extraction directory
+fn make_tool_binaries_executable(extract_dir: &Path) {
+    let mut files = Vec::new();
+    collect_relative_files(extract_dir, extract_dir, &mut files);
+
+    for file in files {
+        let path = extract_dir.join(&file);
+        let is_binary = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| TOOL_BINARY_NAMES.contains(&name));
+        if is_binary && crate::utils::lacks_exec_permission(&path) {
+            if let Err(e) = crate::utils::make_file_executable(&path) {
+                tracing::warn!(error = %e, "Failed to make extracted binary executable");
+            }
+        }
     }
 }
 
+/// Set the execute bit on a pandoc or typst binary that was copied without it (`chmod +x`)
+#[tauri::command]
+pub async fn make_executable(path: String) -> Result<(), String> {
+    crate::utils::make_file_executable(&path)?;
+    tracing::info!(path = %path, "Made executable");
+    Ok(())
+}
+
+/// Remove the macOS quarantine attribute from a downloaded pandoc or typst (file or
+/// directory) and make it executable, so Gatekeeper lets it run
+#[tauri::command]
+pub async fn clear_quarantine(path: String) -> Result<(), String> {
+    crate::utils::clear_quarantine_attr(&path)
+}
+
 /// Extract TAR.XZ archive (for Typst)
-fn extract_tar_xz(archive_path: &PathBuf, extract_dir: &PathBuf) -> Result<String, String> {
+fn extract_tar_xz(
+    archive_path: &PathBuf,
+    extract_dir: &PathBuf,
+    app_handle: Option<&AppHandle>,
+) -> Result<String, String> {
     use std::io::BufReader;
 
     let file =
         std::fs::File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
-    let mut reader = BufReader::new(file);
+    let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut reader = BufReader::new(ProgressReader::new(file, total, app_handle));
 
     // Decompress XZ first
     let mut decompressed = Vec::new();
@@ -1037,18 +2027,93 @@ fn extract_tar_xz(archive_path: &PathBuf, extract_dir: &PathBuf) -> Result<Strin
     Ok(extract_dir.to_string_lossy().to_string())
 }
 
+/// Extract TAR.BZ2 archive (repackaged community mirrors)
+fn extract_tar_bz2(
+    archive_path: &PathBuf,
+    extract_dir: &PathBuf,
+    app_handle: Option<&AppHandle>,
+) -> Result<String, String> {
+    use bzip2::read::BzDecoder;
+
+    let file =
+        std::fs::File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+    let bz = BzDecoder::new(ProgressReader::new(file, total, app_handle));
+    let mut archive = tar::Archive::new(bz);
+
+    archive
+        .unpack(extract_dir)
+        .map_err(|e| format!("Failed to extract TAR.BZ2 archive: {}", e))?;
+
+    Ok(extract_dir.to_string_lossy().to_string())
+}
+
+/// Extract 7Z archive (repackaged community mirrors)
+fn extract_7z(
+    archive_path: &PathBuf,
+    extract_dir: &PathBuf,
+    app_handle: Option<&AppHandle>,
+) -> Result<String, String> {
+    use sevenz_rust::{Password, SevenZReader};
+
+    let mut archive = SevenZReader::open(archive_path, Password::empty())
+        .map_err(|e| format!("Failed to open archive: {}", e))?;
+    let total = archive.archive().files.len() as u64;
+
+    let canonical_extract_dir = extract_dir
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve extract directory: {}", e))?;
+
+    let mut extracted = 0u64;
+    archive
+        .for_each_entries(|entry, reader| {
+            let outpath = sanitize_archive_entry_path(extract_dir, entry.name())
+                .map_err(sevenz_rust::Error::other)?;
+
+            if let Some(parent) = outpath.parent() {
+                std::fs::create_dir_all(parent)?;
+                ensure_within_extract_dir(&canonical_extract_dir, parent, entry.name())
+                    .map_err(sevenz_rust::Error::other)?;
+            }
+
+            sevenz_rust::default_entry_extract_fn(entry, reader, &outpath)?;
+
+            // Set permissions on Unix (p7zip stores the mode in the high attribute bits)
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let attributes = entry.windows_attributes;
+                if entry.has_windows_attributes && attributes & 0x8000 != 0 {
+                    let mode = attributes >> 16;
+                    std::fs::set_permissions(&outpath, std::fs::Permissions::from_mode(mode)).ok();
+                    // Ignore permission errors
+                }
+            }
+
+            extracted += 1;
+            emit_extract_progress(app_handle, extracted, total, "files");
+            Ok(true)
+        })
+        .map_err(|e| format!("Failed to extract 7Z archive: {}", e))?;
+
+    Ok(extract_dir.to_string_lossy().to_string())
+}
+
 /// Download Typst for current platform
 #[tauri::command]
 pub async fn download_typst(
     version: Option<String>,
     download_dir: String,
-) -> Result<String, String> {
-    let config = DownloadConfig::current_platform();
+    app_handle: AppHandle,
+) -> Result<DownloadResult, String> {
+    let config = DownloadConfig::from_settings(&app_handle);
     download_tool(
         DownloadType::Typst,
         version,
         PathBuf::from(download_dir),
         config,
+        Some(&app_handle),
     )
     .await
 }
@@ -1059,6 +2124,79 @@ pub async fn get_latest_typst_release_info() -> Result<GithubRelease, String> {
     get_latest_typst_release().await
 }
 
+/// Get all available Typst releases
+#[tauri::command]
+pub async fn get_typst_releases(limit: Option<u32>) -> Result<Vec<GithubRelease>, String> {
+    get_releases(TYPST_REPO, limit).await
+}
+
+/// Get Typst version comparison info
+#[tauri::command]
+pub async fn get_typst_version_info(
+    current_version: Option<String>,
+) -> Result<VersionInfo, String> {
+    let latest_release = get_latest_typst_release().await?;
+    let latest_version = latest_release.tag_name.clone();
+
+    let releases = get_typst_releases(Some(20)).await?;
+    let available_versions: Vec<String> = releases.into_iter().map(|r| r.tag_name).collect();
+
+    let is_update_available = current_version
+        .as_ref()
+        .map_or(true, |current| is_newer_version(current, &latest_version));
+
+    Ok(VersionInfo {
+        current: current_version,
+        latest: Some(latest_version),
+        available_versions,
+        is_update_available,
+    })
+}
+
+/// Choose the directory managed tool updates are installed into
+/// Release builds use the resource directory when writable; development builds
+/// (whose resources live in the build output) and read-only installs fall back to app data
+fn get_managed_install_dir(app_handle: &AppHandle, tool: &str) -> Result<PathBuf, String> {
+    if !cfg!(debug_assertions) {
+        if let Ok(resource_dir) = app_handle.path().resource_dir() {
+            let dir = resource_dir.join(tool);
+            if is_dir_writable(&dir) {
+                tracing::info!(
+                    tool,
+                    dir = %dir.display(),
+                    "Installing managed tool into resource directory"
+                );
+                return Ok(dir);
+            }
+        }
+    }
+
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    let dir = app_data_dir.join(tool);
+    tracing::info!(
+        tool,
+        dir = %dir.display(),
+        "Installing managed tool into app data directory"
+    );
+    Ok(dir)
+}
+
+/// Check if a directory can be created and written to
+fn is_dir_writable(dir: &PathBuf) -> bool {
+    if std::fs::create_dir_all(dir).is_err() {
+        return false;
+    }
+
+    let probe = dir.join(".write-test");
+    let writable = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
+}
+
 /// Update managed pandoc by downloading latest version  
 #[tauri::command]
 pub async fn update_managed_pandoc(app_handle: AppHandle) -> Result<String, String> {
@@ -1066,71 +2204,686 @@ pub async fn update_managed_pandoc(app_handle: AppHandle) -> Result<String, Stri
     let latest_release = get_latest_pandoc_release().await?;
     let version = latest_release.tag_name.clone();
 
-    // Get resource directory
-    let resource_dir = app_handle
-        .path()
-        .resource_dir()
-        .map_err(|e| format!("Failed to get resource directory: {}", e))?;
+    install_managed_pandoc(&app_handle, &version).await?;
+
+    Ok(format!(
+        "Successfully updated managed pandoc to version {}",
+        version
+    ))
+}
+
+/// Download a pandoc release and swap it in as the managed install
+/// Returns the directory the release was installed into
+async fn install_managed_pandoc(app_handle: &AppHandle, version: &str) -> Result<PathBuf, String> {
+    let pandoc_dir = get_managed_install_dir(app_handle, "pandoc")?;
+
+    // Stage the update in a sibling directory so the live install stays intact until it's verified
+    let staging_dir = pandoc_dir.with_file_name("pandoc.new");
+    if staging_dir.exists() {
+        std::fs::remove_dir_all(&staging_dir)
+            .map_err(|e| format!("Failed to clean staging directory: {}", e))?;
+    }
+    std::fs::create_dir_all(&staging_dir)
+        .map_err(|e| format!("Failed to create staging directory: {}", e))?;
+
+    let staged = stage_pandoc_update(app_handle, version, &staging_dir).await;
+    if let Err(e) = staged {
+        let _ = std::fs::remove_dir_all(&staging_dir);
+        return Err(e);
+    }
 
-    let pandoc_dir = resource_dir.join("pandoc");
+    write_install_manifest(&staging_dir);
+    swap_directories(&staging_dir, &pandoc_dir)?;
 
-    // Create directory if it doesn't exist
-    std::fs::create_dir_all(&pandoc_dir)
-        .map_err(|e| format!("Failed to create pandoc directory: {}", e))?;
+    Ok(pandoc_dir)
+}
 
-    // Download pandoc to resource directory
-    let config = DownloadConfig::current_platform();
+/// Download, extract and verify a pandoc release inside a staging directory
+/// Returns the path of the verified pandoc executable
+async fn stage_pandoc_update(
+    app_handle: &AppHandle,
+    version: &str,
+    staging_dir: &PathBuf,
+) -> Result<PathBuf, String> {
+    let config = DownloadConfig::from_settings(app_handle);
     let download_path = download_tool(
         DownloadType::Pandoc,
-        Some(version.clone()),
-        pandoc_dir.clone(),
+        Some(version.to_string()),
+        staging_dir.clone(),
         config,
+        Some(app_handle),
     )
-    .await?;
+    .await?
+    .path;
 
-    // Extract the archive (this will overwrite existing files)
-    extract_archive_unified(PathBuf::from(download_path), pandoc_dir).await?;
+    let download_path = PathBuf::from(download_path);
+    let extracted =
+        extract_archive_unified(download_path.clone(), staging_dir.clone(), Some(app_handle)).await;
+
+    // The archive isn't part of the install, so it must not be swapped in or recorded
+    // in the install manifest
+    if let Err(e) = std::fs::remove_file(&download_path) {
+        tracing::warn!(error = %e, "Failed to remove downloaded pandoc archive");
+    }
+    extracted?;
+
+    let pandoc_exe = if cfg!(windows) {
+        "pandoc.exe"
+    } else {
+        "pandoc"
+    };
+
+    let staged_pandoc = find_pandoc_in_directory(staging_dir, pandoc_exe)
+        .ok_or_else(|| "Downloaded archive does not contain a pandoc executable".to_string())?;
+
+    if !crate::utils::validate_pandoc_executable(&staged_pandoc) {
+        return Err("Downloaded pandoc failed to run".to_string());
+    }
+
+    Ok(staged_pandoc)
+}
+
+/// Prefix of the temp directories used to try out pandoc versions
+const TEMP_DOWNLOAD_PREFIX: &str = "pandoc-desktop-trial-";
+
+/// Download a pandoc version into a fresh temp directory without touching the managed install
+/// Returns the path of the verified executable
+#[tauri::command]
+pub async fn download_pandoc_temp(
+    version: String,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let unique = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let temp_dir = std::env::temp_dir().join(format!(
+        "{}{}-{}",
+        TEMP_DOWNLOAD_PREFIX,
+        strip_version_prefix(&version),
+        unique
+    ));
+
+    std::fs::create_dir_all(&temp_dir)
+        .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+
+    match stage_pandoc_update(&app_handle, &version, &temp_dir).await {
+        Ok(pandoc_path) => Ok(pandoc_path.to_string_lossy().to_string()),
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&temp_dir);
+            Err(e)
+        }
+    }
+}
+
+/// Remove every temp directory created by `download_pandoc_temp`
+/// Returns the number of directories removed
+#[tauri::command]
+pub async fn cleanup_temp_downloads() -> Result<usize, String> {
+    remove_temp_download_dirs()
+}
+
+/// Remove the `download_pandoc_temp` directories, returning how many were removed
+fn remove_temp_download_dirs() -> Result<usize, String> {
+    let entries = std::fs::read_dir(std::env::temp_dir())
+        .map_err(|e| format!("Failed to read temp directory: {}", e))?;
+
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let is_trial = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.starts_with(TEMP_DOWNLOAD_PREFIX));
+
+        if is_trial && entry.path().is_dir() {
+            std::fs::remove_dir_all(entry.path())
+                .map_err(|e| format!("Failed to remove {}: {}", entry.path().display(), e))?;
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+/// Remove temp downloads and staging directories left behind by interrupted updates
+/// Returns the number of directories removed
+pub fn remove_leftover_downloads(app_handle: &AppHandle) -> usize {
+    let mut removed = remove_temp_download_dirs().unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "Failed to clean up temp downloads");
+        0
+    });
+
+    let base_dirs = [
+        app_handle.path().app_data_dir(),
+        app_handle.path().resource_dir(),
+    ];
+    for base_dir in base_dirs.into_iter().flatten() {
+        for staging_name in ["pandoc.new", "typst.new"] {
+            let staging_dir = base_dir.join(staging_name);
+            if staging_dir.is_dir() && std::fs::remove_dir_all(&staging_dir).is_ok() {
+                removed += 1;
+            }
+        }
+    }
+
+    removed
+}
+
+/// Replace `target_dir` with `new_dir`, keeping a `.bak` copy until the swap succeeds
+fn swap_directories(new_dir: &PathBuf, target_dir: &PathBuf) -> Result<(), String> {
+    let backup_dir = target_dir.with_extension("bak");
+    if backup_dir.exists() {
+        std::fs::remove_dir_all(&backup_dir)
+            .map_err(|e| format!("Failed to remove old backup: {}", e))?;
+    }
+
+    let had_previous = target_dir.exists();
+    if had_previous {
+        std::fs::rename(target_dir, &backup_dir)
+            .map_err(|e| format!("Failed to back up current installation: {}", e))?;
+    }
+
+    if let Err(e) = std::fs::rename(new_dir, target_dir) {
+        // Restore the previous installation so the user keeps a working pandoc
+        if had_previous {
+            let _ = std::fs::rename(&backup_dir, target_dir);
+        }
+        return Err(format!("Failed to activate new installation: {}", e));
+    }
+
+    if had_previous {
+        let _ = std::fs::remove_dir_all(&backup_dir);
+    }
+
+    Ok(())
+}
+
+/// Install a manually downloaded pandoc or typst release archive as the managed install,
+/// for machines that can't reach the release servers
+/// The archive is extracted and verified in a staging directory before it replaces the current install
+#[tauri::command]
+pub async fn import_managed_archive(
+    archive_path: String,
+    tool: String,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    if tool != "pandoc" && tool != "typst" {
+        return Err(format!("Unknown managed tool: {}", tool));
+    }
+
+    let archive = PathBuf::from(&archive_path);
+    if !archive.is_file() {
+        return Err(format!("Archive not found: {}", archive_path));
+    }
+    let is_archive = archive
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(is_archive_name);
+    if !is_archive {
+        return Err(format!("Not a supported archive: {}", archive_path));
+    }
+
+    let install_dir = get_managed_install_dir(&app_handle, &tool)?;
+    let staging_dir = install_dir.with_file_name(format!("{}.new", tool));
+    if staging_dir.exists() {
+        std::fs::remove_dir_all(&staging_dir)
+            .map_err(|e| format!("Failed to clean staging directory: {}", e))?;
+    }
+    std::fs::create_dir_all(&staging_dir)
+        .map_err(|e| format!("Failed to create staging directory: {}", e))?;
+
+    let version = match stage_imported_archive(&app_handle, &archive, &tool, &staging_dir).await {
+        Ok(version) => version,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&staging_dir);
+            return Err(e);
+        }
+    };
+
+    write_install_manifest(&staging_dir);
+    swap_directories(&staging_dir, &install_dir)?;
+
+    if tool == "pandoc" {
+        crate::pandoc::set_cached_pandoc_path(&app_handle, None);
+    }
 
     Ok(format!(
-        "Successfully updated managed pandoc to version {}",
-        version
+        "Successfully imported {} {} into {}",
+        tool,
+        version,
+        install_dir.display()
     ))
 }
 
+/// Extract an imported archive into a staging directory and check the tool runs
+/// Returns the version reported by `--version`
+async fn stage_imported_archive(
+    app_handle: &AppHandle,
+    archive: &Path,
+    tool: &str,
+    staging_dir: &PathBuf,
+) -> Result<String, String> {
+    extract_archive_unified(archive.to_path_buf(), staging_dir.clone(), Some(app_handle)).await?;
+
+    let exe_name = if cfg!(windows) {
+        format!("{}.exe", tool)
+    } else {
+        tool.to_string()
+    };
+    let exe_path = find_pandoc_in_directory(staging_dir, &exe_name)
+        .ok_or_else(|| format!("Archive does not contain a {} executable", tool))?;
+
+    let output = crate::utils::create_hidden_command(&exe_path)
+        .arg("--version")
+        .output()
+        .map_err(|e| crate::utils::describe_exec_error(&exe_path.to_string_lossy(), &e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Imported {} failed to run: {}",
+            tool,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(parse_version(&String::from_utf8_lossy(&output.stdout)))
+}
+
 /// Update managed typst by downloading latest version
 #[tauri::command]
 pub async fn update_managed_typst(app_handle: AppHandle) -> Result<String, String> {
     // Get latest release
-    let config = DownloadConfig::current_platform();
+    let config = DownloadConfig::from_settings(&app_handle);
     let latest_release = get_latest_typst_release().await?;
     let version = latest_release.tag_name.clone();
 
-    // Get resource directory
-    let resource_dir = app_handle
-        .path()
-        .resource_dir()
-        .map_err(|e| format!("Failed to get resource directory: {}", e))?;
+    let typst_dir = get_managed_install_dir(&app_handle, "typst")?;
+
+    // Stage the update in a sibling directory so the live install stays intact until it's complete
+    let staging_dir = typst_dir.with_file_name("typst.new");
+    if staging_dir.exists() {
+        std::fs::remove_dir_all(&staging_dir)
+            .map_err(|e| format!("Failed to clean staging directory: {}", e))?;
+    }
+    std::fs::create_dir_all(&staging_dir)
+        .map_err(|e| format!("Failed to create staging directory: {}", e))?;
+
+    let staged = stage_typst_update(&app_handle, &version, &staging_dir, config).await;
+    if let Err(e) = staged {
+        let _ = std::fs::remove_dir_all(&staging_dir);
+        return Err(e);
+    }
 
-    let typst_dir = resource_dir.join("typst");
+    write_install_manifest(&staging_dir);
+    swap_directories(&staging_dir, &typst_dir)?;
 
-    // Create directory if it doesn't exist
-    std::fs::create_dir_all(&typst_dir)
-        .map_err(|e| format!("Failed to create typst directory: {}", e))?;
+    Ok(format!(
+        "Successfully updated managed typst to version {}",
+        version
+    ))
+}
 
-    // Download typst to resource directory
+/// Download and extract a typst release inside a staging directory
+async fn stage_typst_update(
+    app_handle: &AppHandle,
+    version: &str,
+    staging_dir: &PathBuf,
+    config: DownloadConfig,
+) -> Result<(), String> {
     let download_path = download_tool(
         DownloadType::Typst,
-        Some(version.clone()),
-        typst_dir.clone(),
+        Some(version.to_string()),
+        staging_dir.clone(),
         config,
+        Some(app_handle),
     )
-    .await?;
+    .await?
+    .path;
+
+    let download_path = PathBuf::from(download_path);
+    let extracted =
+        extract_archive_unified(download_path.clone(), staging_dir.clone(), Some(app_handle)).await;
+
+    // The archive isn't part of the install, so it must not be swapped in or recorded
+    // in the install manifest
+    if let Err(e) = std::fs::remove_file(&download_path) {
+        tracing::warn!(error = %e, "Failed to remove downloaded typst archive");
+    }
+    extracted.map(|_| ())
+}
+
+/// File recording the files of a managed install, written once the install is complete
+const INSTALL_MANIFEST: &str = "install-manifest.json";
+
+/// Record every file in a managed install directory so later checks can spot missing ones
+fn write_install_manifest(install_dir: &PathBuf) {
+    let mut files = Vec::new();
+    collect_relative_files(install_dir, install_dir, &mut files);
+    files.retain(|file| file != INSTALL_MANIFEST);
+    files.sort();
+
+    let result = serde_json::to_string_pretty(&files)
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            std::fs::write(install_dir.join(INSTALL_MANIFEST), json).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        tracing::warn!(error = %e, "Failed to write install manifest");
+    }
+}
+
+/// Collect the paths of all files under `dir`, relative to `base_dir` and '/'-separated
+fn collect_relative_files(base_dir: &Path, dir: &Path, files: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_relative_files(base_dir, &path, files);
+        } else if let Ok(relative) = path.strip_prefix(base_dir) {
+            let parts: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            files.push(parts.join("/"));
+        }
+    }
+}
+
+/// Check that a managed tool ("pandoc" or "typst") is still intact
+/// Verifies the executable exists and runs, and that files recorded at install time are present
+#[tauri::command]
+pub async fn verify_managed_install(
+    tool: String,
+    app_handle: AppHandle,
+) -> Result<InstallHealth, String> {
+    if tool != "pandoc" && tool != "typst" {
+        return Err(format!("Unknown managed tool: {}", tool));
+    }
+
+    let exe_name = if cfg!(windows) {
+        format!("{}.exe", tool)
+    } else {
+        tool.clone()
+    };
+
+    // Same lookup order as the managed pandoc path: app data updates first, then resources
+    let mut candidate_dirs = Vec::new();
+    if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
+        candidate_dirs.push(app_data_dir.join(&tool));
+    }
+    if let Ok(resource_dir) = app_handle.path().resource_dir() {
+        candidate_dirs.push(resource_dir.join(&tool));
+    }
+
+    let mut health = InstallHealth {
+        tool: tool.clone(),
+        install_dir: None,
+        executable: None,
+        version: None,
+        missing_files: Vec::new(),
+        problems: Vec::new(),
+        healthy: false,
+    };
+
+    let Some(install_dir) = candidate_dirs.into_iter().find(|dir| dir.is_dir()) else {
+        health
+            .problems
+            .push(format!("No managed {} installation found", tool));
+        return Ok(health);
+    };
+    health.install_dir = Some(install_dir.to_string_lossy().to_string());
+
+    match find_pandoc_in_directory(&install_dir, &exe_name) {
+        Some(exe_path) => {
+            health.executable = Some(exe_path.to_string_lossy().to_string());
+
+            match crate::utils::create_hidden_command(&exe_path)
+                .arg("--version")
+                .output()
+            {
+                Ok(output) if output.status.success() => {
+                    health.version = Some(parse_version(&String::from_utf8_lossy(&output.stdout)));
+                }
+                Ok(output) => health.problems.push(format!(
+                    "{} --version failed: {}",
+                    exe_name,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )),
+                Err(e) => health
+                    .problems
+                    .push(format!("Failed to run {}: {}", exe_name, e)),
+            }
+        }
+        None => health.problems.push(format!(
+            "Executable {} is missing from {}",
+            exe_name,
+            install_dir.display()
+        )),
+    }
+
+    // Installs made before manifests were recorded only get the executable check
+    let manifest_path = install_dir.join(INSTALL_MANIFEST);
+    if manifest_path.exists() {
+        let expected: Vec<String> = std::fs::read_to_string(&manifest_path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+            .map_err(|e| format!("Failed to read install manifest: {}", e))?;
+
+        health.missing_files = expected
+            .into_iter()
+            .filter(|file| !install_dir.join(file).exists())
+            .collect();
+
+        if !health.missing_files.is_empty() {
+            health.problems.push(format!(
+                "{} installed files are missing",
+                health.missing_files.len()
+            ));
+        }
+    }
+
+    health.healthy = health.problems.is_empty();
+    Ok(health)
+}
+
+/// Replace a managed pandoc built for another architecture (e.g. an x86_64 install synced
+/// from an Intel Mac to Apple Silicon) with the build for this machine
+#[tauri::command]
+pub async fn reinstall_for_current_arch(app_handle: AppHandle) -> Result<String, String> {
+    let exe_path = get_managed_pandoc_path(&app_handle)
+        .ok_or_else(|| "No managed pandoc installation found".to_string())?;
+
+    let error = match crate::utils::create_hidden_command(&exe_path)
+        .arg("--version")
+        .output()
+    {
+        Ok(output) if output.status.success() => {
+            return Ok(format!(
+                "Managed pandoc at {} already runs on this machine; nothing to reinstall",
+                exe_path.display()
+            ))
+        }
+        Ok(output) => {
+            return Err(format!(
+                "Managed pandoc at {} failed to run: {}",
+                exe_path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+        Err(e) => crate::utils::describe_exec_error(&exe_path.to_string_lossy(), &e),
+    };
+
+    if !error.starts_with(crate::utils::ARCH_MISMATCH_ERROR) {
+        return Err(error);
+    }
 
-    // Extract the archive
-    extract_archive_unified(PathBuf::from(download_path), typst_dir).await?;
+    let old_arch = crate::utils::detect_architecture(&exe_path.to_string_lossy(), "")
+        .unwrap_or_else(|| "another architecture".to_string());
+    let config = DownloadConfig::from_settings(&app_handle);
+
+    let mut managed_roots = Vec::new();
+    if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
+        managed_roots.push(app_data_dir.join("pandoc"));
+        managed_roots.push(app_data_dir.join("pandoc-portable"));
+    }
+    if let Ok(resource_dir) = app_handle.path().resource_dir() {
+        managed_roots.push(resource_dir.join("pandoc"));
+    }
+    let mismatched_dir = managed_roots
+        .iter()
+        .find_map(|root| managed_install_dir_of(root, &exe_path));
+
+    // Install the new build first, so a failed download leaves the old install in place
+    let version = get_latest_pandoc_release().await?.tag_name;
+    let installed_dir = install_managed_pandoc(&app_handle, &version).await?;
+    crate::pandoc::set_cached_pandoc_path(&app_handle, None);
+
+    // The swap already replaced the mismatched install if it lived in the same directory
+    if let Some(dir) = mismatched_dir.filter(|dir| !dir.starts_with(&installed_dir)) {
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)
+                .map_err(|e| format!("Failed to remove mismatched installation: {}", e))?;
+        }
+    }
 
     Ok(format!(
-        "Successfully updated managed typst to version {}",
-        version
+        "Replaced pandoc built for {} with the {} {} build {}",
+        old_arch, config.target_os, config.target_arch, version
     ))
 }
+
+/// Directory holding the install that `exe_path` belongs to under a managed root:
+/// its version directory, or the root itself for an executable directly in it or its `bin`
+fn managed_install_dir_of(root: &Path, exe_path: &Path) -> Option<PathBuf> {
+    let relative = exe_path.strip_prefix(root).ok()?;
+    let mut components = relative.components();
+    let first = components.next()?;
+
+    // A single remaining component is the executable itself
+    if components.next().is_none() || first.as_os_str() == "bin" {
+        return Some(root.to_path_buf());
+    }
+
+    Some(root.join(first))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create `<base>/<dir>/bin/pandoc` as a stub executable
+    fn create_stub_pandoc(base: &Path, dir: &str) -> PathBuf {
+        let bin_dir = base.join(dir).join("bin");
+        std::fs::create_dir_all(&bin_dir).unwrap();
+        let exe = bin_dir.join("pandoc");
+        std::fs::write(&exe, "#!/bin/sh\necho pandoc\n").unwrap();
+        crate::utils::make_file_executable(&exe).unwrap();
+        exe
+    }
+
+    #[test]
+    fn find_pandoc_in_directory_prefers_newest_version() {
+        // Create the version directories in both orders, since read_dir order is unspecified
+        for dirs in [
+            ["pandoc-3.1.0", "pandoc-3.7.0.2"],
+            ["pandoc-3.7.0.2", "pandoc-3.1.0"],
+        ] {
+            let temp = tempfile::tempdir().unwrap();
+            let base = temp.path().to_path_buf();
+            for dir in dirs {
+                create_stub_pandoc(&base, dir);
+            }
+
+            let found = find_pandoc_in_directory(&base, "pandoc").unwrap();
+            assert_eq!(
+                found,
+                base.join("pandoc-3.7.0.2").join("bin").join("pandoc")
+            );
+        }
+    }
+
+    /// Serve `body` once on a local port, labelled with `Content-Encoding: gzip`
+    fn serve_gzip_encoded_once(body: Vec<u8>) -> String {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            // Read the request headers before answering
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+
+            let headers = format!(
+                "HTTP/1.1 200 OK\r\n\
+                 Content-Type: application/gzip\r\n\
+                 Content-Encoding: gzip\r\n\
+                 Content-Length: {}\r\n\
+                 Connection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(headers.as_bytes()).unwrap();
+            stream.write_all(&body).unwrap();
+        });
+
+        format!("http://{}/pandoc.tar.gz", addr)
+    }
+
+    #[test]
+    fn download_file_keeps_gzip_encoded_archive_intact() {
+        use flate2::write::GzEncoder;
+
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"pretend this is a tarball").unwrap();
+        let archive = encoder.finish().unwrap();
+
+        let url = serve_gzip_encoded_once(archive.clone());
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("pandoc.tar.gz");
+
+        let bytes = tauri::async_runtime::block_on(download_file(
+            &url,
+            &dest,
+            Duration::from_secs(10),
+            None,
+        ))
+        .unwrap();
+
+        // The file must be the archive as served, not the decoded tarball
+        assert_eq!(bytes, archive.len() as u64);
+        assert_eq!(std::fs::read(&dest).unwrap(), archive);
+    }
+
+    #[test]
+    fn managed_install_dir_of_keeps_other_versions() {
+        let root = Path::new("/data/pandoc-portable");
+
+        assert_eq!(
+            managed_install_dir_of(root, &root.join("pandoc-3.1.0/bin/pandoc")),
+            Some(root.join("pandoc-3.1.0"))
+        );
+        assert_eq!(
+            managed_install_dir_of(root, &root.join("bin/pandoc")),
+            Some(root.to_path_buf())
+        );
+        assert_eq!(
+            managed_install_dir_of(root, &root.join("pandoc")),
+            Some(root.to_path_buf())
+        );
+        assert_eq!(
+            managed_install_dir_of(root, Path::new("/usr/bin/pandoc")),
+            None
+        );
+    }
+}
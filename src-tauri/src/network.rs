@@ -0,0 +1,142 @@
+use crate::types::{ConnectivityStatus, ProxyConfig};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tauri_plugin_http::reqwest;
+
+/// Proxy settings used by every HTTP client the app creates
+/// Kept process-wide because release lookups and downloads run without an AppHandle
+static PROXY_CONFIG: RwLock<Option<ProxyConfig>> = RwLock::new(None);
+
+/// Timeout for each `test_connectivity` request
+const CONNECTIVITY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Load the saved proxy settings; called once at startup
+pub fn init(app_handle: &AppHandle) {
+    let proxy = crate::config::load_config(app_handle).proxy;
+    if let Ok(mut current) = PROXY_CONFIG.write() {
+        *current = Some(proxy);
+    }
+}
+
+/// Build the reqwest proxies for a configuration
+/// With nothing configured reqwest falls back to HTTP_PROXY/HTTPS_PROXY/NO_PROXY
+fn build_proxies(config: &ProxyConfig) -> Result<Vec<reqwest::Proxy>, String> {
+    let no_proxy = config
+        .no_proxy
+        .as_deref()
+        .filter(|s| !s.is_empty())
+        .and_then(reqwest::NoProxy::from_string);
+
+    let invalid = |url: &str, e: reqwest::Error| format!("Invalid proxy URL '{}': {}", url, e);
+    let configured = |url: &Option<String>| url.clone().filter(|u| !u.is_empty());
+
+    let mut proxies = Vec::new();
+    if let Some(url) = configured(&config.http) {
+        proxies.push(reqwest::Proxy::http(&url).map_err(|e| invalid(&url, e))?);
+    }
+    if let Some(url) = configured(&config.https) {
+        proxies.push(reqwest::Proxy::https(&url).map_err(|e| invalid(&url, e))?);
+    }
+    // A SOCKS proxy ("socks5://host:port") carries all traffic
+    if let Some(url) = configured(&config.socks) {
+        proxies.push(reqwest::Proxy::all(&url).map_err(|e| invalid(&url, e))?);
+    }
+
+    let proxies = proxies
+        .into_iter()
+        .map(|proxy| proxy.no_proxy(no_proxy.clone()))
+        .collect();
+
+    Ok(proxies)
+}
+
+/// Create a client builder with the configured proxies applied
+pub fn client_builder() -> reqwest::ClientBuilder {
+    let mut builder = reqwest::Client::builder();
+
+    let config = PROXY_CONFIG
+        .read()
+        .ok()
+        .and_then(|config| config.clone())
+        .unwrap_or_default();
+
+    // Settings are validated when saved, so this only fails for hand-edited config files
+    match build_proxies(&config) {
+        Ok(proxies) => {
+            for proxy in proxies {
+                builder = builder.proxy(proxy);
+            }
+        }
+        Err(e) => tracing::warn!(error = %e, "Ignoring invalid proxy settings"),
+    }
+
+    builder
+}
+
+/// Create an HTTP client with the configured proxies applied
+pub fn http_client() -> reqwest::Client {
+    client_builder().build().unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "Failed to build HTTP client, using defaults");
+        reqwest::Client::new()
+    })
+}
+
+/// Get the proxy settings
+#[tauri::command]
+pub async fn get_proxy_config(app_handle: AppHandle) -> ProxyConfig {
+    crate::config::load_config(&app_handle).proxy
+}
+
+/// Save the proxy settings and apply them to all new requests
+#[tauri::command]
+pub async fn set_proxy_config(proxy: ProxyConfig, app_handle: AppHandle) -> Result<(), String> {
+    build_proxies(&proxy)?;
+
+    let mut config = crate::config::load_config(&app_handle);
+    config.proxy = proxy.clone();
+    crate::config::save_config(&app_handle, &config)?;
+
+    if let Ok(mut current) = PROXY_CONFIG.write() {
+        *current = Some(proxy);
+    }
+    Ok(())
+}
+
+/// Check whether UNGH and GitHub are reachable through the configured proxy
+#[tauri::command]
+pub async fn test_connectivity() -> Vec<ConnectivityStatus> {
+    let client = http_client();
+    let services = [
+        ("UNGH", "https://ungh.cc"),
+        ("GitHub API", "https://api.github.com"),
+        ("GitHub", "https://github.com"),
+    ];
+
+    let mut results = Vec::new();
+    for (service, url) in services {
+        let started = Instant::now();
+        let result = client
+            .head(url)
+            .header(reqwest::header::USER_AGENT, "pandoc-desktop")
+            .timeout(CONNECTIVITY_TIMEOUT)
+            .send()
+            .await;
+
+        // Any HTTP answer proves the host is reachable, even an error status
+        let (reachable, error) = match result {
+            Ok(_) => (true, None),
+            Err(e) => (false, Some(e.to_string())),
+        };
+
+        results.push(ConnectivityStatus {
+            service: service.to_string(),
+            url: url.to_string(),
+            reachable,
+            latency_ms: reachable.then(|| started.elapsed().as_millis() as u64),
+            error,
+        });
+    }
+
+    results
+}
@@ -1,7 +1,22 @@
-use crate::types::PandocInfo;
-use crate::utils::{get_search_paths, validate_pandoc_executable};
-use std::path::Path;
-use tauri::Manager;
+use crate::types::{
+    BatchResult, BatchSummary, BenchmarkResult, ConversionMatrix, ConversionOptions,
+    ConversionResult, ConvertAndOpenResult, DiffResult, DocumentStats, FormatCapability,
+    HistoryEntry, LatexInfo, OutputFormatCapability, OutputSpec, PandocInfo, PathDiagnosis,
+    PathStatus, SearchPathEntry,
+};
+use crate::utils::{
+    compare_versions, get_search_paths, parse_compiled_features, parse_user_data_dir,
+    parse_version, resolve_tool_path, validate_pandoc_executable,
+};
+use base64::Engine;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::{Emitter, Manager};
+use tokio::sync::oneshot;
 
 /// Get the default PDF engine for a given output format (based on pandoc manual)
 fn get_default_pdf_engine(output_format: &str) -> &'static str {
@@ -20,7 +35,13 @@ fn get_pdf_engines_for_format(output_format: &str) -> Vec<&'static str> {
     let engines = match output_format {
         "latex" => vec!["pdflatex", "xelatex", "lualatex", "tectonic", "latexmk"],
         "context" => vec!["context"],
-        "html" => vec!["wkhtmltopdf", "weasyprint", "prince", "pagedjs-cli"],
+        "html" => vec![
+            "wkhtmltopdf",
+            "weasyprint",
+            "prince",
+            "pagedjs-cli",
+            HEADLESS_BROWSER_ENGINE,
+        ],
         "ms" => vec!["pdfroff"],
         "typst" => vec!["typst"],
         _ => vec![
@@ -30,12 +51,108 @@ fn get_pdf_engines_for_format(output_format: &str) -> Vec<&'static str> {
             "wkhtmltopdf",
             "weasyprint",
             "typst",
+            HEADLESS_BROWSER_ENGINE,
         ],
     };
 
     engines
 }
 
+/// Engine name for printing pandoc's HTML to PDF with a headless Chromium-based browser,
+/// a last resort that needs no extra install ("chromium" is accepted as an alias)
+const HEADLESS_BROWSER_ENGINE: &str = "chrome";
+
+/// Output formats that need an external PDF engine to be produced
+const ENGINE_OUTPUT_FORMATS: &[&str] = &["pdf"];
+
+/// Get the output formats that require an external PDF engine
+#[tauri::command]
+pub fn get_formats_requiring_engine() -> Vec<String> {
+    ENGINE_OUTPUT_FORMATS
+        .iter()
+        .map(|f| f.to_string())
+        .collect()
+}
+
+/// Check whether a given output format can actually be produced on this machine
+#[tauri::command]
+pub async fn can_convert_to(
+    format: String,
+    app_handle: tauri::AppHandle,
+) -> Result<FormatCapability, String> {
+    let pandoc_cmd = find_pandoc_with_priority(&app_handle)?;
+    let (_, supported_outputs) = get_supported_formats(&pandoc_cmd)?;
+
+    let is_supported = supported_outputs.contains(&format);
+    let requires_engine = ENGINE_OUTPUT_FORMATS.contains(&format.as_str());
+    let available_engines = if requires_engine {
+        check_available_pdf_engines_for_format(&format, &app_handle)
+    } else {
+        Vec::new()
+    };
+    let can_convert = is_supported && (!requires_engine || !available_engines.is_empty());
+
+    Ok(FormatCapability {
+        format,
+        is_supported,
+        requires_engine,
+        available_engines,
+        can_convert,
+    })
+}
+
+/// Get the input/output conversion matrix, annotating outputs that need a PDF engine
+/// Pandoc can convert any readable input to any writable output, so every input
+/// pairs with every output whose `is_available` flag is set
+#[tauri::command]
+pub async fn get_conversion_matrix(
+    custom_path: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<ConversionMatrix, String> {
+    let pandoc_cmd = if let Some(custom_path) = custom_path {
+        custom_path
+    } else {
+        find_pandoc_with_priority(&app_handle)?
+    };
+
+    let (input_formats, output_formats) = get_supported_formats(&pandoc_cmd)?;
+
+    // Engine availability only needs to be checked once
+    let available_pdf_engines = check_available_pdf_engines_for_format("pdf", &app_handle);
+
+    let output_formats = output_formats
+        .into_iter()
+        .map(|format| {
+            let requires_engine = ENGINE_OUTPUT_FORMATS.contains(&format.as_str());
+            let (engines, available_engines) = if requires_engine {
+                (
+                    get_pdf_engines_for_format(&format)
+                        .into_iter()
+                        .map(|e| e.to_string())
+                        .collect(),
+                    available_pdf_engines.clone(),
+                )
+            } else {
+                (Vec::new(), Vec::new())
+            };
+            let is_available = !requires_engine || !available_engines.is_empty();
+
+            OutputFormatCapability {
+                format,
+                requires_engine,
+                engines,
+                available_engines,
+                is_available,
+            }
+        })
+        .collect();
+
+    Ok(ConversionMatrix {
+        input_formats,
+        output_formats,
+    })
+}
+
 /// Check if portable Pandoc is available and working
 #[tauri::command]
 pub async fn check_portable_pandoc(app_handle: tauri::AppHandle) -> Result<bool, String> {
@@ -44,8 +161,12 @@ pub async fn check_portable_pandoc(app_handle: tauri::AppHandle) -> Result<bool,
 }
 
 /// Install portable pandoc with improved download selection
+/// With `force`, any existing install of the version is removed first for a clean install
 #[tauri::command]
-pub async fn install_portable_pandoc(app_handle: tauri::AppHandle) -> Result<String, String> {
+pub async fn install_portable_pandoc(
+    app_handle: tauri::AppHandle,
+    force: Option<bool>,
+) -> Result<String, String> {
     // Get latest release
     let latest_release = crate::manager::get_latest_pandoc_release().await?;
     let version = latest_release.tag_name;
@@ -58,6 +179,30 @@ pub async fn install_portable_pandoc(app_handle: tauri::AppHandle) -> Result<Str
 
     let portable_dir = app_data_dir.join("pandoc-portable");
 
+    // Pandoc archives extract into a "pandoc-<version>" directory
+    let version_dir = portable_dir.join(format!("pandoc-{}", version));
+    let exe_name = if cfg!(windows) {
+        "pandoc.exe"
+    } else {
+        "pandoc"
+    };
+
+    if force.unwrap_or(false) {
+        if version_dir.exists() {
+            std::fs::remove_dir_all(&version_dir)
+                .map_err(|e| format!("Failed to remove existing installation: {}", e))?;
+        }
+    } else if let Some(existing) = crate::manager::find_pandoc_in_directory(&version_dir, exe_name)
+    {
+        if validate_pandoc_executable(&existing) {
+            return Ok(format!(
+                "Portable Pandoc {} is already installed at {}",
+                version,
+                existing.display()
+            ));
+        }
+    }
+
     // Create directory if it doesn't exist
     std::fs::create_dir_all(&portable_dir)
         .map_err(|e| format!("Failed to create portable directory: {}", e))?;
@@ -66,12 +211,19 @@ pub async fn install_portable_pandoc(app_handle: tauri::AppHandle) -> Result<Str
     let download_path = crate::manager::download_pandoc(
         version.clone(),
         portable_dir.to_string_lossy().to_string(),
+        app_handle.clone(),
     )
-    .await?;
+    .await?
+    .path;
 
     // Extract the archive
     let extract_dir = portable_dir.to_string_lossy().to_string();
-    let extracted_path = crate::manager::extract_pandoc_archive(download_path, extract_dir).await?;
+    let extracted_path = crate::manager::extract_archive_unified(
+        std::path::PathBuf::from(download_path),
+        std::path::PathBuf::from(extract_dir),
+        Some(&app_handle),
+    )
+    .await?;
 
     Ok(format!(
         "Successfully installed portable Pandoc {} to {}",
@@ -79,6 +231,33 @@ pub async fn install_portable_pandoc(app_handle: tauri::AppHandle) -> Result<Str
     ))
 }
 
+/// Last pandoc path known to work, kept in managed state so repeated conversions
+/// skip re-running `--version` across every search path
+#[derive(Default)]
+pub struct PandocPathCache(Mutex<Option<String>>);
+
+/// Get the cached pandoc path, if any
+fn get_cached_pandoc_path(app_handle: &tauri::AppHandle) -> Option<String> {
+    let cache = app_handle.try_state::<PandocPathCache>()?;
+    let cached = cache.0.lock().ok()?;
+    cached.clone()
+}
+
+/// Replace (or clear with None) the cached pandoc path
+pub fn set_cached_pandoc_path(app_handle: &tauri::AppHandle, path: Option<String>) {
+    if let Some(cache) = app_handle.try_state::<PandocPathCache>() {
+        if let Ok(mut cached) = cache.0.lock() {
+            *cached = path;
+        }
+    }
+}
+
+/// Forget the cached pandoc path, e.g. after installing or updating pandoc
+#[tauri::command]
+pub fn invalidate_pandoc_cache(app_handle: tauri::AppHandle) {
+    set_cached_pandoc_path(&app_handle, None);
+}
+
 /// Find all possible pandoc installations
 pub fn find_all_pandoc_paths() -> Vec<String> {
     let mut valid_paths = Vec::new();
@@ -105,8 +284,8 @@ pub async fn get_pandoc_info_with_portable(
     // First try managed pandoc (unified bundled/portable)
     let managed_source = crate::manager::PandocManager::new(crate::manager::PandocSource::Managed);
     if let Some(managed_path) = managed_source.get_executable_path(&app_handle) {
-        if validate_pandoc_executable(&managed_path.to_string_lossy()) {
-            println!("Using managed Pandoc: {}", managed_path.display());
+        if validate_pandoc_executable(&managed_path) {
+            tracing::info!(path = %managed_path.display(), "Using managed Pandoc");
             return get_pandoc_info(Some(managed_path.to_string_lossy().to_string())).await;
         }
     }
@@ -115,6 +294,19 @@ pub async fn get_pandoc_info_with_portable(
     get_pandoc_info(custom_path).await
 }
 
+/// List every location searched for pandoc, with whether it exists and actually runs
+#[tauri::command]
+pub async fn get_search_paths_debug() -> Vec<SearchPathEntry> {
+    get_search_paths()
+        .into_iter()
+        .map(|path| SearchPathEntry {
+            exists: Path::new(&path).exists(),
+            is_valid: validate_pandoc_executable(&path),
+            path,
+        })
+        .collect()
+}
+
 /// Get comprehensive pandoc information
 #[tauri::command]
 pub async fn get_pandoc_info(custom_path: Option<String>) -> Result<PandocInfo, String> {
@@ -155,10 +347,11 @@ pub async fn get_pandoc_info(custom_path: Option<String>) -> Result<PandocInfo,
                 .to_string();
 
             // Extract clean version number
-            let version = extract_version_number(&raw_version);
+            let version = parse_version(&raw_version);
 
             // Get supported formats
             let (input_formats, output_formats) = get_supported_formats(&pandoc_cmd)?;
+            let architecture = crate::utils::detect_architecture(&pandoc_cmd, &version_text);
 
             Ok(PandocInfo {
                 version,
@@ -168,14 +361,179 @@ pub async fn get_pandoc_info(custom_path: Option<String>) -> Result<PandocInfo,
                 supported_output_formats: output_formats,
                 detected_paths,
                 search_paths,
+                user_data_dir: parse_user_data_dir(&version_text),
+                architecture,
+                compiled_features: parse_compiled_features(&version_text).unwrap_or_default(),
             })
         }
         Ok(_) => Err(format!("Pandoc at '{}' failed to execute", pandoc_cmd)),
-        Err(e) => Err(format!(
-            "Failed to execute pandoc at '{}': {}",
-            pandoc_cmd, e
-        )),
+        Err(e) => Err(crate::utils::describe_exec_error(&pandoc_cmd, &e)),
+    }
+}
+
+/// Resolve a file option against the directory pandoc runs in, as pandoc itself does
+/// Absolute paths are returned unchanged
+fn resolve_option_path(working_dir: &Path, path: &str) -> PathBuf {
+    working_dir.join(path)
+}
+
+/// Check a highlight style: paths must point to an existing `.theme` file,
+/// anything else is passed through as a built-in style name
+fn validate_highlight_style(style: &str, working_dir: &Path) -> Result<(), String> {
+    let path = Path::new(style);
+    let is_path = path.components().count() > 1 || path.extension().is_some();
+    if !is_path {
+        return Ok(());
+    }
+
+    if !path.extension().is_some_and(|ext| ext == "theme") {
+        return Err(format!(
+            "Highlight style file must be a .theme file: {}",
+            style
+        ));
+    }
+    if !resolve_option_path(working_dir, style).is_file() {
+        return Err(format!("Highlight style file not found: {}", style));
+    }
+
+    Ok(())
+}
+
+/// List pandoc's built-in syntax highlighting styles (e.g. "pygments", "tango")
+#[tauri::command]
+pub async fn list_highlight_styles(
+    custom_path: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Vec<String> {
+    let pandoc_cmd = match custom_path {
+        Some(custom_path) => custom_path,
+        None => match find_pandoc_with_priority(&app_handle) {
+            Ok(path) => path,
+            Err(_) => return Vec::new(),
+        },
+    };
+
+    crate::utils::create_hidden_command(&pandoc_cmd)
+        .arg("--list-highlight-styles")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// List templates installed in the user data directory of the pandoc in use
+#[tauri::command]
+pub async fn list_templates(app_handle: tauri::AppHandle) -> Vec<String> {
+    let Ok(pandoc_cmd) = find_pandoc_with_priority(&app_handle) else {
+        return Vec::new();
+    };
+
+    let data_dir = crate::utils::create_hidden_command(&pandoc_cmd)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| parse_user_data_dir(&String::from_utf8_lossy(&output.stdout)));
+
+    let Some(data_dir) = data_dir else {
+        return Vec::new();
+    };
+
+    let mut templates: Vec<String> = std::fs::read_dir(Path::new(&data_dir).join("templates"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_file())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    templates.sort();
+    templates
+}
+
+/// Export one of pandoc's built-in data files (e.g. "reference.docx", "templates/default.latex")
+/// so it can be customized and passed back as a reference doc or template
+#[tauri::command]
+pub async fn export_default_data_file(
+    name: String,
+    output_path: String,
+    custom_path: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let pandoc_cmd = if let Some(custom_path) = custom_path {
+        custom_path
+    } else {
+        find_pandoc_with_priority(&app_handle)?
+    };
+
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Data file name must not be empty".to_string());
+    }
+
+    let output = crate::utils::create_hidden_command(&pandoc_cmd)
+        .arg(format!("--print-default-data-file={}", name))
+        .output()
+        .map_err(|e| format!("Failed to execute pandoc at '{}': {}", pandoc_cmd, e))?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "Failed to read default data file '{}': {}",
+            name,
+            error_msg.trim()
+        ));
+    }
+
+    // Data files like reference.docx are binary, so write stdout as-is
+    std::fs::write(&output_path, &output.stdout)
+        .map_err(|e| format!("Failed to write {}: {}", output_path, e))?;
+
+    Ok(format!("Exported default {} to {}", name, output_path))
+}
+
+/// Read pandoc's default template for an output format (`pandoc -D <format>`),
+/// e.g. to look up variable names or start a custom template from it
+#[tauri::command]
+pub async fn read_default_template(
+    format: String,
+    custom_path: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let pandoc_cmd = if let Some(custom_path) = custom_path {
+        custom_path
+    } else {
+        find_pandoc_with_priority(&app_handle)?
+    };
+
+    let (_, supported_outputs) = get_supported_formats(&pandoc_cmd)?;
+    validate_format_spec(&format, &supported_outputs, "Output")?;
+
+    let output = crate::utils::create_hidden_command(&pandoc_cmd)
+        .args(["-D", &format])
+        .output()
+        .map_err(|e| format!("Failed to execute pandoc at '{}': {}", pandoc_cmd, e))?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "Failed to read default template for '{}': {}",
+            format,
+            error_msg.trim()
+        ));
     }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
 
 /// Enhanced pandoc path detection with common installation paths
@@ -203,10 +561,78 @@ pub async fn validate_pandoc_path(path: String) -> Result<bool, String> {
 
     match output {
         Ok(output) => Ok(output.status.success()),
+        // `diagnose_custom_path` explains why, e.g. a missing execute bit
         Err(_) => Ok(false),
     }
 }
 
+/// Explain whether a custom path is a usable pandoc and, if not, why
+#[tauri::command]
+pub async fn diagnose_custom_path(path: String) -> PathDiagnosis {
+    let diagnosis = |status: PathStatus, message: String| PathDiagnosis {
+        status,
+        version: None,
+        message,
+    };
+
+    let file = Path::new(&path);
+    if !file.exists() {
+        return diagnosis(PathStatus::Missing, format!("{} does not exist", path));
+    }
+    if file.is_dir() {
+        return diagnosis(
+            PathStatus::NotExecutable,
+            format!("{} is a directory, not the pandoc executable", path),
+        );
+    }
+
+    if crate::utils::lacks_exec_permission(file) {
+        return diagnosis(
+            PathStatus::NotExecutable,
+            format!(
+                "{} is not marked as executable; use \"Make Executable\" to fix it",
+                path
+            ),
+        );
+    }
+
+    if let Some(message) = crate::utils::quarantine_error(file) {
+        return diagnosis(PathStatus::Quarantined, message);
+    }
+
+    let output = match crate::utils::create_hidden_command(file)
+        .arg("--version")
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => {
+            let message = crate::utils::describe_exec_error(&path, &e);
+            let status = if message.starts_with(crate::utils::ARCH_MISMATCH_ERROR) {
+                PathStatus::WrongArchitecture
+            } else {
+                PathStatus::NotExecutable
+            };
+            return diagnosis(status, message);
+        }
+    };
+
+    let version_text = String::from_utf8_lossy(&output.stdout);
+    let first_line = version_text.lines().next().unwrap_or("").to_lowercase();
+    if !output.status.success() || !first_line.starts_with("pandoc") {
+        return diagnosis(
+            PathStatus::NotPandoc,
+            format!("{} runs, but it isn't pandoc", path),
+        );
+    }
+
+    let version = parse_version(&version_text);
+    PathDiagnosis {
+        status: PathStatus::Valid,
+        message: format!("Pandoc {}", version),
+        version: Some(version),
+    }
+}
+
 /// Get supported input and output formats with improved error handling
 pub fn get_supported_formats(pandoc_cmd: &str) -> Result<(Vec<String>, Vec<String>), String> {
     // Real Pandoc 3.7.0.2 formats as fallback (based on actual output)
@@ -375,45 +801,358 @@ pub fn get_supported_formats(pandoc_cmd: &str) -> Result<(Vec<String>, Vec<Strin
 
 /// Helper function to find pandoc with unified priority logic
 fn find_pandoc_with_priority(app_handle: &tauri::AppHandle) -> Result<String, String> {
-    // 1. Try managed pandoc first (unified bundled/portable)
-    let managed_source = crate::manager::PandocManager::new(crate::manager::PandocSource::Managed);
-    if let Some(managed_path) = managed_source.get_executable_path(app_handle) {
-        if validate_pandoc_executable(&managed_path.to_string_lossy()) {
-            let path_str = managed_path.to_string_lossy().to_string();
-            return Ok(path_str);
+    // 0. Reuse the last known-good path while it still runs
+    if let Some(cached_path) = get_cached_pandoc_path(app_handle) {
+        if validate_pandoc_executable(&cached_path) {
+            return Ok(cached_path);
         }
+        set_cached_pandoc_path(app_handle, None);
     }
 
-    // 2. Try system paths
-    let detected_paths = find_all_pandoc_paths();
+    let path_str = find_pandoc_uncached(app_handle)?;
+    set_cached_pandoc_path(app_handle, Some(path_str.clone()));
+    Ok(path_str)
+}
 
-    if detected_paths.is_empty() {
-        return Err("Pandoc not found. Please check your installation or specify a custom path in settings.".to_string());
-    }
+/// Run the full managed/system pandoc search, bypassing the path cache
+fn find_pandoc_uncached(app_handle: &tauri::AppHandle) -> Result<String, String> {
+    let preference = crate::config::load_config(app_handle).source_preference;
+
+    let find_managed = || {
+        let managed_source =
+            crate::manager::PandocManager::new(crate::manager::PandocSource::Managed);
+        managed_source
+            .get_executable_path(app_handle)
+            .filter(|path| validate_pandoc_executable(path))
+            // A lossy conversion would name a different file; such paths can't be passed on
+            .and_then(|path| path.into_os_string().into_string().ok())
+    };
+    let find_system = || find_all_pandoc_paths().into_iter().next();
+
+    // Managed (unified bundled/portable) first unless the user prefers their system install
+    let found = if preference.prefers_system() {
+        preference
+            .allows_system()
+            .then(find_system)
+            .flatten()
+            .or_else(|| preference.allows_managed().then(find_managed).flatten())
+    } else {
+        preference
+            .allows_managed()
+            .then(find_managed)
+            .flatten()
+            .or_else(|| preference.allows_system().then(find_system).flatten())
+    };
 
-    let path_str = detected_paths[0].clone();
-    Ok(path_str)
+    found.ok_or_else(|| {
+        "Pandoc not found. Please check your installation or specify a custom path in settings."
+            .to_string()
+    })
 }
 
 /// Enhanced pandoc conversion with correct PDF engine handling
+/// Everything besides the input, output and output format is set through `options`
+/// Every attempt, successful or not, is recorded in the conversion history
+/// Pandoc runs in the input file's directory unless `working_dir` is given; relative
+/// input and output paths are then resolved against that directory
+/// Relative option paths (bibliography, template, filters, ...) always resolve against
+/// the directory pandoc runs in
+/// With `sandbox`, pandoc (3.x) may only read the input and its own data files, which makes it
+/// safe for untrusted documents; options that need wider filesystem access are rejected
+/// With `use_cache`, an unchanged conversion (same input, options and referenced files)
+/// copies the previous output from the app cache instead of running pandoc
+/// For `chunkedhtml`, `output_file` is the directory the site is written to (unless it ends
+/// in .zip) and `split_level` sets the heading level pages are split at
+/// With `toc`, a table of contents is generated down to `toc_depth` (1-6); text formats
+/// such as HTML are then made standalone, since pandoc only emits the TOC in full documents
+/// An explicit `input_format` that doesn't fit the input's extension adds a warning to the result
+/// With `self_contained`, HTML output becomes a single file with images and CSS inlined
+/// `metadata_files` (YAML or JSON) are passed as `--metadata-file` in order; later files
+/// override earlier ones, and the document's own metadata and `variables` override both
 #[tauri::command]
 pub async fn convert_with_pandoc(
     input_file: String,
     output_file: String,
-    input_format: Option<String>,
     output_format: String,
+    options: ConversionOptions,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let started = Instant::now();
+    let entry = new_history_entry(
+        &input_file,
+        &output_file,
+        &options.input_format,
+        &output_format,
+        &options.pdf_engine,
+    );
+
+    let result = run_pandoc_conversion(
+        input_file,
+        output_file,
+        output_format,
+        &options,
+        &app_handle,
+    )
+    .await;
+
+    finish_history_entry(&app_handle, entry, started, &result);
+    result
+}
+
+/// Start a history entry for a conversion that is about to run
+fn new_history_entry(
+    input_file: &str,
+    output_file: &str,
+    input_format: &Option<String>,
+    output_format: &str,
+    pdf_engine: &Option<String>,
+) -> HistoryEntry {
+    HistoryEntry {
+        input_file: input_file.to_string(),
+        output_file: output_file.to_string(),
+        input_format: input_format.clone(),
+        output_format: output_format.to_string(),
+        pdf_engine: pdf_engine.clone(),
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        duration_ms: 0,
+        success: false,
+        error: None,
+    }
+}
+
+/// Complete a history entry with the conversion's outcome and record it
+fn finish_history_entry(
+    app_handle: &tauri::AppHandle,
+    mut entry: HistoryEntry,
+    started: Instant,
+    result: &Result<String, String>,
+) {
+    entry.duration_ms = started.elapsed().as_millis() as u64;
+    entry.success = result.is_ok();
+    entry.error = result.as_ref().err().cloned();
+
+    // History is best effort and must never fail a conversion
+    if let Err(e) = crate::history::record_conversion(app_handle, &entry) {
+        tracing::warn!(error = %e, "Failed to record conversion history");
+    }
+
+    if let Err(e) = result {
+        tracing::error!(input = %entry.input_file, error = %e, "Conversion failed");
+    }
+}
+
+/// Number of outputs `convert_multi_output` converts at the same time
+const MAX_PARALLEL_OUTPUTS: usize = 3;
+
+/// Convert one input to several outputs (e.g. PDF, docx and HTML), a few at a time
+/// Each output spec can carry its own conversion options (engine, template, TOC, ...)
+/// Returns one result per output spec, in the same order, and a summary; each conversion
+/// is recorded in the history
+/// With `stop_on_error`, outputs not yet started when one fails are skipped and reported
+/// as cancelled; conversions already running are left to finish
+#[tauri::command]
+pub async fn convert_multi_output(
+    input_file: String,
+    input_format: Option<String>,
+    outputs: Vec<OutputSpec>,
     custom_pandoc_path: Option<String>,
-    pdf_engine: Option<String>,
+    stop_on_error: Option<bool>,
     app_handle: tauri::AppHandle,
+) -> BatchResult {
+    use futures_util::stream::{self, StreamExt};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let batch_started = Instant::now();
+    let stop_on_error = stop_on_error.unwrap_or(false);
+    let failed = AtomicBool::new(false);
+
+    let results: Vec<ConversionResult> = stream::iter(outputs)
+        .map(|spec| {
+            let input_file = input_file.clone();
+            let input_format = input_format.clone();
+            let custom_pandoc_path = custom_pandoc_path.clone();
+            let app_handle = app_handle.clone();
+            let failed = &failed;
+
+            async move {
+                if stop_on_error && failed.load(Ordering::SeqCst) {
+                    return ConversionResult {
+                        output_file: spec.output_file,
+                        output_format: spec.output_format,
+                        success: false,
+                        cancelled: true,
+                        message: "Skipped after an earlier output failed".to_string(),
+                    };
+                }
+
+                // The batch-wide input format and pandoc apply unless the spec sets its own
+                let options = ConversionOptions {
+                    input_format: spec.options.input_format.or(input_format),
+                    custom_pandoc_path: spec.options.custom_pandoc_path.or(custom_pandoc_path),
+                    ..spec.options
+                };
+
+                let started = Instant::now();
+                let entry = new_history_entry(
+                    &input_file,
+                    &spec.output_file,
+                    &options.input_format,
+                    &spec.output_format,
+                    &options.pdf_engine,
+                );
+                let result = run_pandoc_conversion(
+                    input_file,
+                    spec.output_file.clone(),
+                    spec.output_format.clone(),
+                    &options,
+                    &app_handle,
+                )
+                .await;
+
+                finish_history_entry(&app_handle, entry, started, &result);
+
+                let success = result.is_ok();
+                if !success {
+                    failed.store(true, Ordering::SeqCst);
+                }
+                ConversionResult {
+                    output_file: spec.output_file,
+                    output_format: spec.output_format,
+                    success,
+                    cancelled: false,
+                    message: result.unwrap_or_else(|e| e),
+                }
+            }
+        })
+        .buffered(MAX_PARALLEL_OUTPUTS)
+        .collect()
+        .await;
+
+    let count = |f: fn(&ConversionResult) -> bool| results.iter().filter(|r| f(r)).count();
+    let summary = BatchSummary {
+        total: results.len(),
+        succeeded: count(|r| r.success),
+        failed: count(|r| !r.success && !r.cancelled),
+        cancelled: count(|r| r.cancelled),
+        elapsed_ms: batch_started.elapsed().as_millis() as u64,
+    };
+
+    BatchResult { results, summary }
+}
+
+/// Run a single pandoc conversion (see `convert_with_pandoc`)
+async fn run_pandoc_conversion(
+    input_file: String,
+    output_file: String,
+    output_format: String,
+    options: &ConversionOptions,
+    app_handle: &tauri::AppHandle,
 ) -> Result<String, String> {
+    let ConversionOptions {
+        input_format,
+        custom_pandoc_path,
+        pdf_engine,
+        reference_doc,
+        bibliography,
+        csl,
+        verbose,
+        filters,
+        lua_filters,
+        data_dir,
+        line_ending,
+        template,
+        variables,
+        extract_media,
+        overwrite,
+        epub_cover,
+        epub_metadata,
+        defaults_file,
+        working_dir,
+        sandbox,
+        use_cache,
+        highlight_style,
+        split_level,
+        toc,
+        toc_depth,
+        self_contained,
+        metadata_files,
+        extra_args,
+        conversion_id,
+    } = options.clone();
+
     // Determine which pandoc path to use with unified priority logic
     let pandoc_cmd = if let Some(custom_path) = custom_pandoc_path {
         custom_path
     } else {
-        let detected_path = find_pandoc_with_priority(&app_handle)?;
+        let detected_path = find_pandoc_with_priority(app_handle)?;
         detected_path
     };
 
+    // An explicit working directory replaces the input file's directory for relative paths
+    let working_dir = match working_dir.filter(|dir| !dir.is_empty()) {
+        Some(dir) => {
+            let dir = PathBuf::from(dir);
+            if !dir.is_dir() {
+                return Err(format!(
+                    "Working directory does not exist: {}",
+                    dir.display()
+                ));
+            }
+            Some(dir)
+        }
+        None => None,
+    };
+    // Paths stay PathBufs up to the command line, so non-UTF-8 names aren't mangled
+    let (input_file, output_file) = match &working_dir {
+        Some(dir) => (dir.join(&input_file), dir.join(&output_file)),
+        None => (PathBuf::from(input_file), PathBuf::from(output_file)),
+    };
+    let working_dir =
+        working_dir.unwrap_or_else(|| input_file.parent().unwrap_or(Path::new(".")).to_path_buf());
+
+    // Fill in the output format's profile defaults; explicit parameters win
+    let profile = crate::config::load_config(app_handle)
+        .format_profiles
+        .remove(&output_format)
+        .unwrap_or_default();
+    let pdf_engine = pdf_engine.filter(|e| !e.is_empty()).or(profile.pdf_engine);
+    let template = template.filter(|t| !t.is_empty()).or(profile.template);
+
+    // Caller-supplied arguments follow the profile's so they take precedence
+    let mut extra_args_all = profile.extra_args;
+    extra_args_all.extend(extra_args.unwrap_or_default());
+
+    let sandbox = sandbox.unwrap_or(false);
+    if sandbox {
+        check_sandbox_conflicts(&filters, &lua_filters, &extra_args_all)?;
+    }
+
+    // Fail with a readable message instead of pandoc's "unknown option/format" errors
+    let mut features = Vec::new();
+    if let Some(input_fmt) = input_format.as_deref().filter(|f| *f != "auto") {
+        features.push(format!("{} input", base_format(input_fmt)));
+    }
+    features.push(format!("{} output", base_format(&output_format)));
+    if sandbox {
+        features.push("--sandbox".to_string());
+    }
+    if split_level.is_some() {
+        features.push("--split-level".to_string());
+    }
+    if defaults_file.as_deref().is_some_and(|f| !f.is_empty()) {
+        features.push("--defaults".to_string());
+    }
+    if bibliography.as_ref().is_some_and(|b| !b.is_empty()) {
+        features.push("--citeproc".to_string());
+    }
+    check_feature_support(&pandoc_cmd, &features)?;
+    if lua_filters.as_ref().is_some_and(|f| !f.is_empty()) {
+        check_lua_support(&pandoc_cmd)?;
+    }
+
     // Validate output format only (input format is optional for auto-detection)
     let (_, supported_outputs) = get_supported_formats(&pandoc_cmd).unwrap_or_else(|_| {
         // If format detection fails, use fallback formats
@@ -439,117 +1178,2321 @@ pub async fn convert_with_pandoc(
         ));
     }
 
+    // Resolve line ending up front so an invalid value fails before converting
+    let line_ending = resolve_line_ending(line_ending.as_deref())?;
+
+    check_output_path(&input_file, &output_file, overwrite.unwrap_or(true))?;
+
+    // chunkedhtml writes a multi-page site into a directory unless a .zip is requested
+    let output_is_dir = base_format(&output_format) == "chunkedhtml"
+        && !output_file
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+
+    // Turn pandoc's opaque permission errors into an actionable message
+    if output_is_dir {
+        std::fs::create_dir_all(&output_file)
+            .map_err(|e| format!("Failed to create output directory: {}", e))?;
+        crate::utils::check_output_writable(&output_file.join("index.html"))?;
+    } else {
+        crate::utils::check_output_writable(&output_file)?;
+    }
+
     // Build command arguments
     let mut args = Vec::new();
 
+    // A defaults file goes first: pandoc lets later options override it,
+    // so the explicit -f/-t/-o and other parameters below always win
+    if let Some(defaults_file) = defaults_file.filter(|f| !f.is_empty()) {
+        validate_defaults_file(&defaults_file, &working_dir)?;
+        args.push(format!("--defaults={}", defaults_file));
+    }
+
+    // Profile and extra arguments come before the explicit options so those can override them
+    args.extend(extra_args_all);
+
+    if sandbox {
+        args.push("--sandbox".to_string());
+    }
+
     // Add input format only if specified (otherwise let Pandoc auto-detect)
+    let mut format_warning = None;
     if let Some(input_fmt) = input_format {
         if !input_fmt.is_empty() && input_fmt != "auto" {
+            format_warning = input_format_mismatch(&input_file, &input_fmt);
             args.push("-f".to_string());
             args.push(input_fmt);
         }
     }
 
-    // Add output format
+    // Special handling for PDF output with correct engine selection
+    let engine = if output_format == "pdf" {
+        Some(select_pdf_engine(&output_format, pdf_engine, app_handle)?)
+    } else {
+        None
+    };
+    let browser = match engine.as_deref() {
+        Some(HEADLESS_BROWSER_ENGINE) => Some(
+            crate::utils::find_chromium_browser()
+                .ok_or_else(|| "No Chrome, Edge or Chromium browser found".to_string())?,
+        ),
+        _ => None,
+    };
+
+    // Add output format; for the headless browser pandoc writes self-contained HTML to print
     args.push("-t".to_string());
-    args.push(output_format.clone());
+    if browser.is_some() {
+        args.push("html5".to_string());
+        args.extend(embed_resources_args(&pandoc_cmd));
+    } else {
+        args.push(output_format.clone());
+    }
 
-    // Special handling for PDF output with correct engine selection
-    if output_format == "pdf" {
+    if let (Some(engine), None) = (engine, &browser) {
+        // Use the engine directly (it may already be a full path from get_best_typst_path)
+        args.extend(typst_engine_args(&engine, app_handle)?);
         args.push("--pdf-engine".to_string());
+        args.push(engine);
+    }
 
-        // Use user-specified engine or determine best default for output format
-        let engine_to_use = if let Some(user_engine) = pdf_engine {
-            // Validate that the user-specified engine is available
-            let available_engines =
-                check_available_pdf_engines_for_format(&output_format, &app_handle);
+    // Use a project-local data directory for templates, filters and reference docs
+    if let Some(data_dir) = data_dir.filter(|d| !d.is_empty()) {
+        if !resolve_option_path(&working_dir, &data_dir).is_dir() {
+            return Err(format!("Pandoc data directory not found: {}", data_dir));
+        }
+        args.push(format!("--data-dir={}", data_dir));
+    }
 
-            if available_engines.contains(&user_engine) {
-                user_engine
-            } else {
-                return Err(format!(
-                    "Specified PDF engine '{}' is not available.\n\
-                     Available engines for PDF output: {}",
-                    user_engine,
-                    available_engines.join(", ")
-                ));
-            }
-        } else {
-            // Auto-select best available engine for this output format
-            let default_engine = get_default_pdf_engine(&output_format);
-            let available_engines =
-                check_available_pdf_engines_for_format(&output_format, &app_handle);
-
-            // Try default engine first, then fallback to any available
-            if available_engines.contains(&default_engine.to_string()) {
-                default_engine.to_string()
-            } else if let Some(engine) = available_engines.first() {
-                engine.clone()
-            } else {
-                return Err(format!(
-                    "PDF conversion failed: No PDF engine found for output format '{}'.\n\n\
-                     Recommended PDF engines for {}:\n\
-                     {}\n\n\
-                     Installation guides:\n\
-                     • typst: 'cargo install typst-cli' or download from GitHub releases\n\
-                     • wkhtmltopdf: Download from https://wkhtmltopdf.org/\n\
-                     • weasyprint: 'pip install weasyprint'\n\
-                     • LaTeX distribution (TeX Live, MiKTeX): For academic publishing\n\n\
-                     Alternatively, try converting to HTML first, then use a browser to print to PDF.",
-                     output_format,
-                     output_format,
-                     get_pdf_engines_for_format(&output_format).join(", ")
-                ));
-            }
-        };
+    // Add custom reference document (None or empty keeps pandoc's default template)
+    if let Some(reference_doc) = reference_doc {
+        if !reference_doc.is_empty() {
+            validate_reference_doc(&reference_doc, &output_format, &working_dir)?;
+            args.push(format!("--reference-doc={}", reference_doc));
+        }
+    }
 
-        // Use the engine directly (it may already be a full path from get_best_typst_path)
-        args.push(engine_to_use);
+    // Add custom template
+    if let Some(template) = template.filter(|t| !t.is_empty()) {
+        if !resolve_option_path(&working_dir, &template).exists() {
+            return Err(format!("Template file not found: {}", template));
+        }
+        args.push(format!("--template={}", template));
+    }
+
+    // Add code highlighting style: a named style ("tango") or a .theme file
+    if let Some(style) = highlight_style.filter(|s| !s.is_empty()) {
+        validate_highlight_style(&style, &working_dir)?;
+        args.push(format!("--highlight-style={}", style));
+    }
+
+    if let Some(split_level) = split_level {
+        args.push(format!("--split-level={}", split_level));
+    }
+
+    // Inline images, CSS and scripts so the HTML file can be shared on its own
+    let mut self_contained_flag = None;
+    if self_contained.unwrap_or(false) && HTML_OUTPUT_FORMATS.contains(&base_format(&output_format))
+    {
+        let embed_args = embed_resources_args(&pandoc_cmd);
+        self_contained_flag = Some(embed_args.join(" "));
+        args.extend(embed_args);
+    }
+
+    // A TOC is only written into standalone documents; binary formats always are
+    let mut added_standalone = false;
+    if toc.unwrap_or(false) {
+        args.push("--toc".to_string());
+        if let Some(depth) = toc_depth {
+            args.push(format!("--toc-depth={}", depth.clamp(1, 6)));
+        }
+
+        let is_standalone = BINARY_OUTPUT_FORMATS.contains(&base_format(&output_format))
+            || args.iter().any(|arg| arg == "-s" || arg == "--standalone");
+        if !is_standalone {
+            args.push("--standalone".to_string());
+            added_standalone = true;
+        }
+    }
+
+    // Add template variables; each is its own argv element, so spaces need no quoting
+    if let Some(variables) = variables {
+        // Sort for a stable command line
+        let mut variables: Vec<_> = variables.into_iter().collect();
+        variables.sort();
+        for (key, value) in variables {
+            if key.is_empty() || key.contains('=') {
+                return Err(format!("Invalid template variable name: '{}'", key));
+            }
+            args.push("-V".to_string());
+            args.push(format!("{}={}", key, value));
+        }
+    }
+
+    // Shared metadata files, validated here so a typo doesn't surface as a pandoc parse error
+    for metadata_file in metadata_files.unwrap_or_default() {
+        let is_metadata = Path::new(&metadata_file)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                ["yaml", "yml", "json"]
+                    .iter()
+                    .any(|allowed| ext.eq_ignore_ascii_case(allowed))
+            });
+        if !is_metadata {
+            return Err(format!(
+                "Metadata file must be a YAML or JSON file: {}",
+                metadata_file
+            ));
+        }
+        if !resolve_option_path(&working_dir, &metadata_file).is_file() {
+            return Err(format!("Metadata file not found: {}", metadata_file));
+        }
+        args.push(format!("--metadata-file={}", metadata_file));
+    }
+
+    // Extract embedded images instead of inlining or dropping them
+    let media_dir = match extract_media.filter(|d| !d.is_empty()) {
+        Some(media_dir) => {
+            let media_path = prepare_media_dir(&media_dir, &working_dir)?;
+            args.push(format!("--extract-media={}", media_path.display()));
+            Some(media_path)
+        }
+        None => None,
+    };
+
+    // Add EPUB cover image and metadata
+    args.extend(build_epub_args(
+        &output_format,
+        epub_cover.filter(|c| !c.is_empty()),
+        epub_metadata.filter(|m| !m.is_empty()),
+        &working_dir,
+    )?);
+
+    // Add citation processing when a bibliography is provided
+    if let Some(bibliography) = bibliography.filter(|files| !files.is_empty()) {
+        args.extend(build_citation_args(
+            &bibliography,
+            csl.as_deref(),
+            &working_dir,
+        )?);
+    }
+
+    // Add filters in the given order (pandoc applies them sequentially)
+    for filter in filters.unwrap_or_default() {
+        let resolved = resolve_tool_path(&filter, &pandoc_cmd, &working_dir)
+            .ok_or_else(|| format!("Filter '{}' not found", filter))?;
+        args.push("--filter".to_string());
+        args.push(resolved);
+    }
+
+    for filter in lua_filters.unwrap_or_default() {
+        // Unresolved bare names are passed through so pandoc can look in its data directory
+        let resolved = match resolve_tool_path(&filter, &pandoc_cmd, &working_dir) {
+            Some(path) => path,
+            None if Path::new(&filter).components().count() == 1 => filter,
+            None => return Err(format!("Lua filter '{}' not found", filter)),
+        };
+        args.push("--lua-filter".to_string());
+        args.push(resolved);
+    }
+
+    // Verbose output is streamed to the frontend line by line
+    let verbose = verbose.unwrap_or(false);
+    if verbose {
+        args.push("--verbose".to_string());
+    }
+
+    // The browser prints an intermediate HTML file to the actual output
+    let print_html = browser.as_ref().map(|_| {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!(
+            "pandoc-desktop-print-{}-{}.html",
+            std::process::id(),
+            unique
+        ))
+    });
+
+    // The input and output paths are added to the command line separately, as paths
+    let output_target = print_html.as_ref().unwrap_or(&output_file);
+
+    // Extracted media, directory outputs and browser-printed PDFs can't be restored from
+    // the cache, so those always run
+    let cache_key = if use_cache.unwrap_or(false)
+        && media_dir.is_none()
+        && !output_is_dir
+        && print_html.is_none()
+    {
+        Some(crate::cache::conversion_cache_key(
+            &pandoc_cmd,
+            &args,
+            &input_file,
+            &working_dir,
+            line_ending,
+        )?)
+    } else {
+        None
+    };
+
+    if let Some(key) = &cache_key {
+        if crate::cache::restore_cached_output(app_handle, key, &output_file)? {
+            let mut message = format!(
+                "Successfully converted {} to {} (cached)",
+                input_file.display(),
+                output_file.display()
+            );
+            if let Some(warning) = &format_warning {
+                message.push_str(&format!("; warning: {}", warning));
+            }
+            return Ok(message);
+        }
+    }
+
+    // Execute conversion
+    tracing::info!(
+        pandoc = %pandoc_cmd,
+        argv = ?args,
+        input = %input_file.display(),
+        output = %output_target.display(),
+        "Running pandoc conversion"
+    );
+
+    let command = pandoc_command(&pandoc_cmd, &args, &input_file, output_target, &working_dir);
+
+    let output = run_cancellable(command, conversion_id.as_deref(), verbose, app_handle)
+        .await
+        .map_err(|e| format!("Failed to execute pandoc at '{}': {}", pandoc_cmd, e))?;
+
+    let Some(output) = output else {
+        // Don't leave a half-written file behind
+        let _ = std::fs::remove_file(&output_file);
+        if let Some(html) = &print_html {
+            let _ = std::fs::remove_file(html);
+        }
+        return Err("Conversion cancelled".to_string());
+    };
+
+    if let (Some(browser), Some(html)) = (&browser, &print_html) {
+        let printed = if output.status.success() {
+            print_html_to_pdf(browser, html, &output_file)
+        } else {
+            Ok(())
+        };
+        let _ = std::fs::remove_file(html);
+        printed?;
+    }
+
+    if output.status.success() {
+        // Binary formats must not be touched
+        if !BINARY_OUTPUT_FORMATS.contains(&output_format.as_str()) {
+            normalize_line_endings(&output_file, line_ending)?;
+        }
+
+        // Caching is best effort; the conversion itself succeeded
+        if let Some(key) = &cache_key {
+            if let Err(e) = crate::cache::store_cached_output(app_handle, key, &output_file) {
+                tracing::warn!(error = %e, "Failed to cache conversion output");
+            }
+        }
+
+        let mut message = format!(
+            "Successfully converted {} to {}",
+            input_file.display(),
+            output_file.display()
+        );
+        if output_is_dir {
+            message.push_str(&format!(
+                "; site index: {}",
+                output_file.join("index.html").display()
+            ));
+        }
+        if let Some(warning) = format_warning {
+            message.push_str(&format!("; warning: {}", warning));
+        }
+        if let Some(browser) = &browser {
+            message.push_str(&format!("; printed to PDF with {}", browser.display()));
+        }
+        if let Some(flag) = self_contained_flag {
+            message.push_str(&format!("; embedded resources with {}", flag));
+        }
+        if added_standalone {
+            message.push_str("; added --standalone so the table of contents is included");
+        }
+        if let Some(media_dir) = media_dir {
+            message.push_str(&format!(
+                "; extracted {} media files to {}",
+                count_files(&media_dir),
+                media_dir.display()
+            ));
+        }
+
+        Ok(message)
+    } else {
+        let error_msg =
+            String::from_utf8(output.stderr).unwrap_or_else(|_| "Unknown pandoc error".to_string());
+
+        // Provide helpful error messages for common PDF issues
+        if output_format == "pdf" && error_msg.contains("not found") {
+            Err(format!(
+                "PDF conversion failed: Required engine not found.\n\n\
+                Solutions:\n\
+                1. Install the recommended PDF engine for this format\n\
+                2. Alternative: Convert to HTML first, then print to PDF from browser\n\
+                3. Try a different output format (html, docx, etc.)\n\n\
+                Original error: {}",
+                error_msg.trim()
+            ))
+        } else {
+            Err(format!("Pandoc conversion failed: {}", error_msg.trim()))
+        }
+    }
+}
+
+/// Build a pandoc command line running in `working_dir`
+/// The input and output are passed as paths rather than strings, so names that aren't
+/// valid UTF-8 reach pandoc unchanged
+fn pandoc_command(
+    pandoc_cmd: &str,
+    args: &[String],
+    input_file: &Path,
+    output_file: &Path,
+    working_dir: &Path,
+) -> Command {
+    let mut command = crate::utils::create_hidden_command(pandoc_cmd);
+    command
+        .args(args)
+        .arg(input_file)
+        .arg("-o")
+        .arg(output_file)
+        .current_dir(working_dir);
+    command
+}
+
+/// Check that a `--defaults` file exists and is YAML
+fn validate_defaults_file(defaults_file: &str, working_dir: &Path) -> Result<(), String> {
+    let path = resolve_option_path(working_dir, defaults_file);
+    let is_yaml = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("yaml") || e.eq_ignore_ascii_case("yml"));
+
+    if !is_yaml {
+        return Err(format!(
+            "Defaults file must be a .yaml or .yml file: {}",
+            defaults_file
+        ));
+    }
+    if !path.is_file() {
+        return Err(format!("Defaults file not found: {}", defaults_file));
+    }
+
+    Ok(())
+}
+
+/// Output formats accepting `--epub-cover-image` and `--epub-metadata`
+const EPUB_OUTPUT_FORMATS: &[&str] = &["epub", "epub2", "epub3"];
+
+/// Image types supported as EPUB covers
+const EPUB_COVER_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg"];
+
+/// Build the EPUB cover and metadata arguments, rejecting them for non-EPUB output
+fn build_epub_args(
+    output_format: &str,
+    cover: Option<String>,
+    metadata: Option<String>,
+    working_dir: &Path,
+) -> Result<Vec<String>, String> {
+    if cover.is_none() && metadata.is_none() {
+        return Ok(Vec::new());
+    }
+
+    if !EPUB_OUTPUT_FORMATS.contains(&output_format) {
+        return Err(format!(
+            "EPUB cover and metadata are only supported for EPUB output, not '{}'",
+            output_format
+        ));
+    }
+
+    let mut args = Vec::new();
+
+    if let Some(cover) = cover {
+        let extension = Path::new(&cover)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+
+        if !EPUB_COVER_EXTENSIONS.contains(&extension.as_str()) {
+            return Err(format!(
+                "EPUB cover image must be a PNG or JPEG file: {}",
+                cover
+            ));
+        }
+        if !resolve_option_path(working_dir, &cover).is_file() {
+            return Err(format!("EPUB cover image not found: {}", cover));
+        }
+        args.push(format!("--epub-cover-image={}", cover));
+    }
+
+    if let Some(metadata) = metadata {
+        if !resolve_option_path(working_dir, &metadata).is_file() {
+            return Err(format!("EPUB metadata file not found: {}", metadata));
+        }
+        args.push(format!("--epub-metadata={}", metadata));
+    }
+
+    Ok(args)
+}
+
+/// Error code prefix for an existing output file when overwriting is disabled
+const OUTPUT_EXISTS_ERROR: &str = "output_exists";
+
+/// Refuse to overwrite the input file, and existing outputs unless `overwrite` is set
+fn check_output_path(input_file: &Path, output_file: &Path, overwrite: bool) -> Result<(), String> {
+    if !output_file.exists() {
+        return Ok(());
+    }
+
+    // Canonicalize so "./report.md" and "/docs/report.md" are recognized as the same file
+    let same_file = match (input_file.canonicalize(), output_file.canonicalize()) {
+        (Ok(input), Ok(output)) => input == output,
+        _ => false,
+    };
+
+    if same_file {
+        return Err("Output path is the same as the input file".to_string());
+    }
+
+    if !overwrite {
+        return Err(format!(
+            "{}: Output file already exists: {}",
+            OUTPUT_EXISTS_ERROR,
+            output_file.display()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Resolve and create the `--extract-media` directory
+fn prepare_media_dir(media_dir: &str, working_dir: &Path) -> Result<PathBuf, String> {
+    let media_path = resolve_option_path(working_dir, media_dir);
+
+    // Pointing at a file (e.g. the input document itself) would clobber it
+    if media_path.is_file() {
+        return Err(format!(
+            "Media directory '{}' points at an existing file",
+            media_dir
+        ));
+    }
+
+    std::fs::create_dir_all(&media_path)
+        .map_err(|e| format!("Failed to create media directory: {}", e))?;
+
+    Ok(media_path)
+}
+
+/// Count the files below a directory (recursively)
+fn count_files(dir: &Path) -> usize {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| {
+                    let path = entry.path();
+                    if path.is_dir() {
+                        count_files(&path)
+                    } else {
+                        1
+                    }
+                })
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Open a file with the OS default application
+/// Goes through the shell plugin rather than `cmd /C start`, which would interpret `&`, `^`
+/// and `%` in the file name
+#[allow(deprecated)]
+fn open_with_default_app(app_handle: &tauri::AppHandle, path: &str) -> Result<(), String> {
+    use tauri_plugin_shell::ShellExt;
+
+    app_handle
+        .shell()
+        .open(path, None)
+        .map_err(|e| format!("Failed to open '{}': {}", path, e))
+}
+
+/// Convert a document and, if `open_after` (default true), open the result with the default app
+#[tauri::command]
+pub async fn convert_and_open(
+    input_file: String,
+    output_file: String,
+    output_format: String,
+    options: ConversionOptions,
+    open_after: Option<bool>,
+    app_handle: tauri::AppHandle,
+) -> Result<ConvertAndOpenResult, String> {
+    let message = convert_with_pandoc(
+        input_file,
+        output_file.clone(),
+        output_format,
+        options,
+        app_handle.clone(),
+    )
+    .await?;
+
+    let opened_path = if open_after.unwrap_or(true) {
+        open_with_default_app(&app_handle, &output_file)?;
+        Some(output_file)
+    } else {
+        None
+    };
+
+    Ok(ConvertAndOpenResult {
+        message,
+        opened_path,
+    })
+}
+
+/// PDF engines already warmed up in this session, kept in managed state
+#[derive(Default)]
+pub struct WarmedPdfEngines(Mutex<HashSet<String>>);
+
+/// Prime a PDF engine's caches (fonts, formats, packages) with a throwaway conversion,
+/// so the user's first real PDF conversion doesn't pay the cold start
+/// Each engine is warmed at most once per session; an empty engine means the default one
+#[tauri::command]
+pub async fn warm_pdf_engine(engine: String, app_handle: tauri::AppHandle) -> Result<(), String> {
+    let pandoc_cmd = find_pandoc_with_priority(&app_handle)?;
+    let engine = select_pdf_engine("pdf", Some(engine).filter(|e| !e.is_empty()), &app_handle)?;
+    // A browser has no caches worth priming
+    if engine == HEADLESS_BROWSER_ENGINE {
+        return Ok(());
+    }
+
+    let warmed = app_handle.state::<WarmedPdfEngines>();
+    // Claim the engine up front so concurrent calls don't warm it twice
+    if !warmed
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(engine.clone())
+    {
+        return Ok(());
+    }
+
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let temp_base = std::env::temp_dir().join(format!(
+        "pandoc-desktop-warm-{}-{}",
+        std::process::id(),
+        unique
+    ));
+    let input_file = temp_base.with_extension("md");
+    let output_file = temp_base.with_extension("pdf");
+
+    let result = std::fs::write(&input_file, "# Warm-up\n\nHello, *world*.\n")
+        .map_err(|e| format!("Failed to write warm-up document: {}", e))
+        .and_then(|_| {
+            crate::utils::create_hidden_command(&pandoc_cmd)
+                .arg(&input_file)
+                .args(["-t", "pdf", "--pdf-engine"])
+                .arg(&engine)
+                .arg("-o")
+                .arg(&output_file)
+                .output()
+                .map_err(|e| format!("Failed to execute pandoc at '{}': {}", pandoc_cmd, e))
+        })
+        .and_then(|output| {
+            if output.status.success() {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Warming up {} failed: {}",
+                    engine,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ))
+            }
+        });
+
+    let _ = std::fs::remove_file(&input_file);
+    let _ = std::fs::remove_file(&output_file);
+
+    match &result {
+        Ok(()) => tracing::info!(engine = %engine, "PDF engine warmed up"),
+        Err(_) => {
+            // Let a later call retry
+            if let Ok(mut warmed) = warmed.0.lock() {
+                warmed.remove(&engine);
+            }
+        }
+    }
+
+    result
+}
+
+/// Time the same conversion with each PDF engine (or once with the default for other formats)
+/// Each engine runs `runs` times (at least 2); the first run is a discarded warm-up
+#[tauri::command]
+pub async fn benchmark_conversion(
+    input_file: String,
+    output_format: String,
+    engines: Vec<String>,
+    runs: u32,
+    app_handle: tauri::AppHandle,
+) -> Vec<BenchmarkResult> {
+    let runs = runs.max(2);
+    let engines: Vec<Option<String>> = if engines.is_empty() {
+        vec![None]
+    } else {
+        engines.into_iter().map(Some).collect()
+    };
+
+    let mut results = Vec::new();
+    for engine in engines {
+        let engine_label = engine.clone().unwrap_or_else(|| "default".to_string());
+        // Engines may be given as paths, which can't go into a file name as is
+        let engine_stem = Path::new(&engine_label)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| engine_label.clone());
+        let output_file = std::env::temp_dir().join(format!(
+            "pandoc-desktop-bench-{}.{}",
+            sanitize_file_stem(&engine_stem),
+            output_format
+        ));
+        let output_file = output_file.to_string_lossy().to_string();
+
+        let mut timings = Vec::new();
+        let mut error = None;
+        for run in 0..runs {
+            let started = Instant::now();
+            let options = ConversionOptions {
+                pdf_engine: engine.clone(),
+                overwrite: Some(true),
+                ..Default::default()
+            };
+            let result = run_pandoc_conversion(
+                input_file.clone(),
+                output_file.clone(),
+                output_format.clone(),
+                &options,
+                &app_handle,
+            )
+            .await;
+
+            if let Err(e) = result {
+                error = Some(e);
+                break;
+            }
+            if run > 0 {
+                timings.push(started.elapsed().as_secs_f64() * 1000.0);
+            }
+        }
+
+        let output_size = std::fs::metadata(&output_file).ok().map(|m| m.len());
+        let _ = std::fs::remove_file(&output_file);
+
+        timings.sort_by(|a, b| a.total_cmp(b));
+        let median_ms = match timings.len() {
+            0 => 0.0,
+            n if n % 2 == 0 => (timings[n / 2 - 1] + timings[n / 2]) / 2.0,
+            n => timings[n / 2],
+        };
+
+        results.push(BenchmarkResult {
+            engine: engine_label,
+            runs: timings.len() as u32,
+            min_ms: timings.first().copied().unwrap_or(0.0),
+            median_ms,
+            mean_ms: if timings.is_empty() {
+                0.0
+            } else {
+                timings.iter().sum::<f64>() / timings.len() as f64
+            },
+            output_size: if error.is_none() { output_size } else { None },
+            error,
+        });
+    }
+
+    results
+}
+
+/// Average silent reading speed used for the reading time estimate
+const WORDS_PER_MINUTE: usize = 200;
+/// Rough word count of a typeset page with default pandoc PDF settings
+const WORDS_PER_PAGE: usize = 500;
+
+/// Estimate document size (words, reading time, pages, headings, images)
+#[tauri::command]
+pub async fn analyze_document(
+    input_file: String,
+    input_format: Option<String>,
+    custom_path: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<DocumentStats, String> {
+    let pandoc_cmd = if let Some(custom_path) = custom_path {
+        custom_path
+    } else {
+        find_pandoc_with_priority(&app_handle)?
+    };
+
+    let input_format = input_format.filter(|fmt| !fmt.is_empty() && fmt != "auto");
+
+    // Plain text gives an honest word count; the native AST exposes the structure
+    let plain = run_pandoc_to_string(&pandoc_cmd, &input_file, input_format.as_deref(), "plain")?;
+    let native = run_pandoc_to_string(&pandoc_cmd, &input_file, input_format.as_deref(), "native")?;
+
+    let word_count = plain.split_whitespace().count();
+
+    // Constructors appear as bare tokens, while document text is always quoted
+    let mut heading_count = 0;
+    let mut image_count = 0;
+    for token in native.split(|c: char| c.is_whitespace() || "[](),".contains(c)) {
+        match token {
+            "Header" => heading_count += 1,
+            "Image" => image_count += 1,
+            _ => {}
+        }
+    }
+
+    Ok(DocumentStats {
+        word_count,
+        reading_time_minutes: word_count.div_ceil(WORDS_PER_MINUTE) as u32,
+        estimated_pages: word_count.div_ceil(WORDS_PER_PAGE).max(1) as u32,
+        heading_count,
+        image_count,
+    })
+}
+
+/// Run pandoc on a file and capture the converted document from stdout
+fn run_pandoc_to_string(
+    pandoc_cmd: &str,
+    input_file: &str,
+    input_format: Option<&str>,
+    output_format: &str,
+) -> Result<String, String> {
+    let mut cmd = crate::utils::create_hidden_command(pandoc_cmd);
+
+    if let Some(input_fmt) = input_format {
+        cmd.args(["-f", input_fmt]);
+    }
+
+    let working_dir = Path::new(input_file).parent().unwrap_or(Path::new("."));
+
+    let output = cmd
+        .args(["-t", output_format, input_file])
+        .current_dir(working_dir)
+        .output()
+        .map_err(|e| format!("Failed to execute pandoc at '{}': {}", pandoc_cmd, e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Pandoc failed to read document: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parse a document without producing output, returning pandoc's warnings and errors
+/// A clean document returns an empty list
+#[tauri::command]
+pub async fn validate_document(
+    input_file: String,
+    input_format: Option<String>,
+    custom_path: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<String>, String> {
+    let pandoc_cmd = if let Some(custom_path) = custom_path {
+        custom_path
+    } else {
+        find_pandoc_with_priority(&app_handle)?
+    };
+
+    let null_device = if cfg!(target_os = "windows") {
+        "NUL"
+    } else {
+        "/dev/null"
+    };
+
+    let mut args = Vec::new();
+
+    if let Some(input_fmt) = input_format {
+        if !input_fmt.is_empty() && input_fmt != "auto" {
+            args.push("-f".to_string());
+            args.push(input_fmt);
+        }
+    }
+
+    // The native writer is the cheapest way to force a full parse
+    args.push("-t".to_string());
+    args.push("native".to_string());
+    args.push(input_file.clone());
+    args.push("-o".to_string());
+    args.push(null_device.to_string());
+
+    let working_dir = Path::new(&input_file).parent().unwrap_or(Path::new("."));
+
+    let output = crate::utils::create_hidden_command(&pandoc_cmd)
+        .args(&args)
+        .current_dir(working_dir)
+        .output()
+        .map_err(|e| format!("Failed to execute pandoc at '{}': {}", pandoc_cmd, e))?;
+
+    let mut messages: Vec<String> = String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .map(|line| line.trim_end().to_string())
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+
+    if !output.status.success() && messages.is_empty() {
+        messages.push(format!(
+            "Pandoc failed to parse the document (exit code {})",
+            output
+                .status
+                .code()
+                .map_or("unknown".to_string(), |c| c.to_string())
+        ));
+    }
+
+    Ok(messages)
+}
+
+/// Convert a document and return the output bytes base64-encoded (for in-app previews)
+#[tauri::command]
+pub async fn convert_to_bytes(
+    input_file: String,
+    input_format: Option<String>,
+    output_format: String,
+    custom_pandoc_path: Option<String>,
+    pdf_engine: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let pandoc_cmd = if let Some(custom_path) = custom_pandoc_path {
+        custom_path
+    } else {
+        find_pandoc_with_priority(&app_handle)?
+    };
+
+    let output = convert_to_stdout(
+        &pandoc_cmd,
+        &input_file,
+        input_format,
+        &output_format,
+        pdf_engine,
+        &app_handle,
+    )?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(output))
+}
+
+/// Resolution used when rasterizing a page preview
+const PREVIEW_DPI: &str = "96";
+
+/// Render the first page of a document to a PNG image, e.g. for a thumbnail
+/// The document is converted to PDF with the best available engine and page one is
+/// rasterized with pdftoppm or mutool; without either, typst renders the page directly
+#[tauri::command]
+pub async fn render_first_page_image(
+    input_file: String,
+    input_format: Option<String>,
+    output_image: String,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let output_path = Path::new(&output_image);
+    if !output_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+    {
+        return Err(format!(
+            "Preview image must be a .png file: {}",
+            output_image
+        ));
+    }
+    if let Some(parent) = output_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create preview directory: {}", e))?;
+    }
+
+    let pandoc_cmd = find_pandoc_with_priority(&app_handle)?;
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+
+    if let Some(rasterizer) = ["pdftoppm", "mutool"]
+        .into_iter()
+        .find_map(|tool| crate::utils::find_in_path(tool).ok())
+    {
+        let engine = select_pdf_engine("pdf", None, &app_handle)
+            .map_err(|e| format!("Cannot render a preview without a PDF engine.\n\n{}", e))?;
+        let pdf = convert_to_stdout(
+            &pandoc_cmd,
+            &input_file,
+            input_format,
+            "pdf",
+            Some(engine),
+            &app_handle,
+        )?;
+
+        let pdf_path = std::env::temp_dir().join(format!(
+            "pandoc-desktop-preview-{}-{}.pdf",
+            std::process::id(),
+            unique
+        ));
+        std::fs::write(&pdf_path, pdf)
+            .map_err(|e| format!("Failed to write preview PDF: {}", e))?;
+
+        let mut cmd = crate::utils::create_hidden_command(&rasterizer);
+        if Path::new(&rasterizer)
+            .file_stem()
+            .is_some_and(|stem| stem.eq_ignore_ascii_case("mutool"))
+        {
+            cmd.args(["draw", "-r", PREVIEW_DPI, "-o"])
+                .arg(output_path)
+                .arg(&pdf_path)
+                .arg("1");
+        } else {
+            // pdftoppm appends the .png extension itself
+            cmd.args([
+                "-png",
+                "-singlefile",
+                "-f",
+                "1",
+                "-l",
+                "1",
+                "-r",
+                PREVIEW_DPI,
+            ])
+            .arg(&pdf_path)
+            .arg(output_path.with_extension(""));
+        }
+        let output = cmd.output();
+        let _ = std::fs::remove_file(&pdf_path);
+
+        let output = output.map_err(|e| format!("Failed to run '{}': {}", rasterizer, e))?;
+        if !output.status.success() || !output_path.is_file() {
+            return Err(format!(
+                "Rendering the preview with '{}' failed: {}",
+                rasterizer,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    } else if let Some(typst_path) = get_best_typst_path(&app_handle) {
+        let source = convert_to_stdout(
+            &pandoc_cmd,
+            &input_file,
+            input_format,
+            "typst",
+            None,
+            &app_handle,
+        )?;
+
+        // Written next to the input so relative image paths still resolve
+        let input_dir = Path::new(&input_file).parent().unwrap_or(Path::new("."));
+        let source_path = input_dir.join(format!(
+            ".pandoc-desktop-preview-{}-{}.typ",
+            std::process::id(),
+            unique
+        ));
+        std::fs::write(&source_path, source)
+            .map_err(|e| format!("Failed to write preview source: {}", e))?;
+
+        // Typst needs a page number template in image output paths
+        let page_template = output_path.with_extension("preview-{p}.png");
+        let output = crate::utils::create_hidden_command(&typst_path)
+            .args(["compile", "--pages", "1"])
+            .arg(&source_path)
+            .arg(&page_template)
+            .output();
+        let _ = std::fs::remove_file(&source_path);
+
+        let output =
+            output.map_err(|e| format!("Failed to run typst at '{}': {}", typst_path, e))?;
+        let first_page = output_path.with_extension("preview-1.png");
+        if !output.status.success() || !first_page.is_file() {
+            return Err(format!(
+                "Rendering the preview with typst failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        std::fs::rename(&first_page, output_path)
+            .map_err(|e| format!("Failed to move preview image: {}", e))?;
+    } else {
+        return Err("No tool found to render page images.\n\n\
+             Install poppler (pdftoppm) or MuPDF (mutool), or install Typst"
+            .to_string());
+    }
+
+    Ok(output_image)
+}
+
+/// Run a conversion with output to stdout and return the raw output
+fn convert_to_stdout(
+    pandoc_cmd: &str,
+    input_file: &str,
+    input_format: Option<String>,
+    output_format: &str,
+    pdf_engine: Option<String>,
+    app_handle: &tauri::AppHandle,
+) -> Result<Vec<u8>, String> {
+    let mut args = Vec::new();
+
+    if let Some(input_fmt) = input_format {
+        if !input_fmt.is_empty() && input_fmt != "auto" {
+            args.push("-f".to_string());
+            args.push(input_fmt);
+        }
+    }
+
+    args.push("-t".to_string());
+    args.push(output_format.to_string());
+
+    if output_format == "pdf" {
+        let engine = select_pdf_engine(output_format, pdf_engine, app_handle)?;
+        reject_browser_engine(&engine)?;
+        args.extend(typst_engine_args(&engine, app_handle)?);
+        args.push("--pdf-engine".to_string());
+        args.push(engine);
+    }
+
+    // Write to stdout so nothing user-visible is created
+    args.push(input_file.to_string());
+    args.push("-o".to_string());
+    args.push("-".to_string());
+
+    let working_dir = Path::new(input_file).parent().unwrap_or(Path::new("."));
+
+    let output = crate::utils::create_hidden_command(pandoc_cmd)
+        .args(&args)
+        .current_dir(working_dir)
+        .output()
+        .map_err(|e| format!("Failed to execute pandoc at '{}': {}", pandoc_cmd, e))?;
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        Err(format!("Pandoc conversion failed: {}", error_msg.trim()))
+    }
+}
+
+/// Longest unified diff returned by `diff_conversions`; longer diffs are cut off
+const MAX_DIFF_LENGTH: usize = 200_000;
+
+/// Convert the same input with two pandoc executables and compare the outputs,
+/// e.g. to check what a pandoc update changes before switching to it
+/// Text outputs get line and word counts plus a unified diff; binary outputs only
+/// their sizes and whether they are identical
+#[tauri::command]
+pub async fn diff_conversions(
+    input_file: String,
+    input_format: Option<String>,
+    output_format: String,
+    pandoc_path_a: String,
+    pandoc_path_b: String,
+    pdf_engine: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<DiffResult, String> {
+    let convert = |pandoc_cmd: &str| {
+        convert_to_stdout(
+            pandoc_cmd,
+            &input_file,
+            input_format.clone(),
+            &output_format,
+            pdf_engine.clone(),
+            &app_handle,
+        )
+        .map_err(|e| format!("{}: {}", pandoc_cmd, e))
+    };
+    let output_a = convert(&pandoc_path_a)?;
+    let output_b = convert(&pandoc_path_b)?;
+
+    let mut result = DiffResult {
+        identical: output_a == output_b,
+        binary: true,
+        size_a: output_a.len() as u64,
+        size_b: output_b.len() as u64,
+        lines_added: 0,
+        lines_removed: 0,
+        words_added: 0,
+        words_removed: 0,
+        diff: None,
+    };
+
+    let texts = (
+        std::str::from_utf8(&output_a),
+        std::str::from_utf8(&output_b),
+    );
+    let (Ok(text_a), Ok(text_b)) = texts else {
+        return Ok(result);
+    };
+    if BINARY_OUTPUT_FORMATS.contains(&base_format(&output_format)) {
+        return Ok(result);
+    }
+    result.binary = false;
+
+    let line_diff = similar::TextDiff::from_lines(text_a, text_b);
+    for change in line_diff.iter_all_changes() {
+        match change.tag() {
+            similar::ChangeTag::Insert => result.lines_added += 1,
+            similar::ChangeTag::Delete => result.lines_removed += 1,
+            similar::ChangeTag::Equal => {}
+        }
+    }
+
+    let word_diff = similar::TextDiff::from_words(text_a, text_b);
+    for change in word_diff.iter_all_changes() {
+        // Whitespace runs are tokens of their own; only count actual words
+        if change.value().trim().is_empty() {
+            continue;
+        }
+        match change.tag() {
+            similar::ChangeTag::Insert => result.words_added += 1,
+            similar::ChangeTag::Delete => result.words_removed += 1,
+            similar::ChangeTag::Equal => {}
+        }
+    }
+
+    if !result.identical {
+        let mut diff = line_diff
+            .unified_diff()
+            .header(&pandoc_path_a, &pandoc_path_b)
+            .to_string();
+        if diff.len() > MAX_DIFF_LENGTH {
+            let mut cut = MAX_DIFF_LENGTH;
+            while !diff.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            diff.truncate(cut);
+            diff.push_str("\n... diff truncated\n");
+        }
+        result.diff = Some(diff);
+    }
+
+    Ok(result)
+}
+
+/// Convert a document to pandoc's JSON AST, as used by filters and external tools
+#[tauri::command]
+pub async fn to_json_ast(
+    input_file: String,
+    input_format: Option<String>,
+    custom_path: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<serde_json::Value, String> {
+    let pandoc_cmd = if let Some(custom_path) = custom_path {
+        custom_path
+    } else {
+        find_pandoc_with_priority(&app_handle)?
+    };
+
+    let input_format = input_format.filter(|fmt| !fmt.is_empty() && fmt != "auto");
+    let json = run_pandoc_to_string(&pandoc_cmd, &input_file, input_format.as_deref(), "json")?;
+
+    serde_json::from_str(&json).map_err(|e| format!("Failed to parse pandoc JSON AST: {}", e))
+}
+
+/// Read a document's metadata (title, author, date, ...) as JSON
+/// Values are rendered as plain text, e.g. `{"title": "Report", "author": ["A", "B"]}`
+#[tauri::command]
+pub async fn read_metadata(
+    input_file: String,
+    input_format: Option<String>,
+    custom_path: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<serde_json::Value, String> {
+    let pandoc_cmd = if let Some(custom_path) = custom_path {
+        custom_path
+    } else {
+        find_pandoc_with_priority(&app_handle)?
+    };
+
+    // A template holding only $meta-json$ makes pandoc print the metadata and nothing else
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let template_path = std::env::temp_dir().join(format!(
+        "pandoc-desktop-meta-{}-{}.tpl",
+        std::process::id(),
+        unique
+    ));
+    std::fs::write(&template_path, "$meta-json$\n")
+        .map_err(|e| format!("Failed to write metadata template: {}", e))?;
+
+    let mut cmd = crate::utils::create_hidden_command(&pandoc_cmd);
+    if let Some(input_fmt) = input_format.filter(|fmt| !fmt.is_empty() && fmt != "auto") {
+        cmd.args(["-f", &input_fmt]);
+    }
+
+    let working_dir = Path::new(&input_file).parent().unwrap_or(Path::new("."));
+
+    let output = cmd
+        .args(["-t", "plain"])
+        .arg("--template")
+        .arg(&template_path)
+        .arg(&input_file)
+        .current_dir(working_dir)
+        .output();
+    let _ = std::fs::remove_file(&template_path);
+
+    let output =
+        output.map_err(|e| format!("Failed to execute pandoc at '{}': {}", pandoc_cmd, e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Pandoc failed to read document: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse document metadata: {}", e))
+}
+
+/// Render a pandoc JSON AST (e.g. from `to_json_ast`) to an output file
+#[tauri::command]
+pub async fn from_json_ast(
+    ast: serde_json::Value,
+    output_file: String,
+    output_format: String,
+    custom_path: Option<String>,
+    pdf_engine: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let pandoc_cmd = if let Some(custom_path) = custom_path {
+        custom_path
+    } else {
+        find_pandoc_with_priority(&app_handle)?
+    };
+
+    let mut args = vec![
+        "-f".to_string(),
+        "json".to_string(),
+        "-t".to_string(),
+        output_format.clone(),
+    ];
+
+    if output_format == "pdf" {
+        let engine = select_pdf_engine(&output_format, pdf_engine, &app_handle)?;
+        reject_browser_engine(&engine)?;
+        args.extend(typst_engine_args(&engine, &app_handle)?);
+        args.push("--pdf-engine".to_string());
+        args.push(engine);
     }
 
-    // Add input and output files
-    args.push(input_file.clone());
     args.push("-o".to_string());
     args.push(output_file.clone());
 
-    // Execute conversion
-    // Set proper working directory for pandoc execution
-    let working_dir = std::path::Path::new(&input_file)
-        .parent()
-        .unwrap_or(std::path::Path::new("."));
+    let input = serde_json::to_vec(&ast).map_err(|e| format!("Failed to serialize AST: {}", e))?;
+
+    let mut child = crate::utils::create_hidden_command(&pandoc_cmd)
+        .args(&args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute pandoc at '{}': {}", pandoc_cmd, e))?;
+
+    // Pandoc reads all of its input before writing, so this can't deadlock on stderr
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(&input)
+            .map_err(|e| format!("Failed to send AST to pandoc: {}", e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to wait for pandoc: {}", e))?;
+
+    if output.status.success() {
+        Ok(format!("Successfully rendered AST to {}", output_file))
+    } else {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        Err(format!("Pandoc conversion failed: {}", error_msg.trim()))
+    }
+}
+
+/// Bibliography formats pandoc can read
+const BIBLIOGRAPHY_INPUT_FORMATS: &[&str] = &["bibtex", "biblatex", "csljson", "ris", "endnotexml"];
+
+/// Bibliography formats pandoc can write (RIS and EndNote XML are input-only)
+const BIBLIOGRAPHY_OUTPUT_FORMATS: &[&str] = &["bibtex", "biblatex", "csljson"];
+
+/// Convert a bibliography between formats, e.g. BibTeX to CSL JSON
+#[tauri::command]
+pub async fn convert_bibliography(
+    input_file: String,
+    input_format: String,
+    output_file: String,
+    output_format: String,
+    custom_path: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    if !BIBLIOGRAPHY_INPUT_FORMATS.contains(&input_format.as_str()) {
+        return Err(format!(
+            "'{}' is not a bibliography input format. Supported: {}",
+            input_format,
+            BIBLIOGRAPHY_INPUT_FORMATS.join(", ")
+        ));
+    }
+    if !BIBLIOGRAPHY_OUTPUT_FORMATS.contains(&output_format.as_str()) {
+        return Err(format!(
+            "'{}' is not a bibliography output format. Supported: {}",
+            output_format,
+            BIBLIOGRAPHY_OUTPUT_FORMATS.join(", ")
+        ));
+    }
+    if !Path::new(&input_file).is_file() {
+        return Err(format!("Bibliography file not found: {}", input_file));
+    }
+    check_output_path(Path::new(&input_file), Path::new(&output_file), true)?;
+
+    let pandoc_cmd = if let Some(custom_path) = custom_path {
+        custom_path
+    } else {
+        find_pandoc_with_priority(&app_handle)?
+    };
+
+    // Older pandoc versions lack some of these readers and writers
+    let (supported_inputs, supported_outputs) = get_supported_formats(&pandoc_cmd)?;
+    validate_format_spec(&input_format, &supported_inputs, "Input")?;
+    validate_format_spec(&output_format, &supported_outputs, "Output")?;
+
+    let output = crate::utils::create_hidden_command(&pandoc_cmd)
+        .args(["-f", &input_format, "-t", &output_format, "--standalone"])
+        .arg(&input_file)
+        .arg("-o")
+        .arg(&output_file)
+        .output()
+        .map_err(|e| format!("Failed to execute pandoc at '{}': {}", pandoc_cmd, e))?;
+
+    if output.status.success() {
+        Ok(format!(
+            "Successfully converted bibliography {} to {}",
+            input_file, output_file
+        ))
+    } else {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        Err(format!(
+            "Bibliography conversion failed: {}",
+            error_msg.trim()
+        ))
+    }
+}
+
+/// Reject options that need filesystem access pandoc's `--sandbox` mode doesn't allow
+fn check_sandbox_conflicts(
+    filters: &Option<Vec<String>>,
+    lua_filters: &Option<Vec<String>>,
+    extra_args: &[String],
+) -> Result<(), String> {
+    let has_filters = filters.as_ref().is_some_and(|f| !f.is_empty())
+        || lua_filters.as_ref().is_some_and(|f| !f.is_empty());
+    if has_filters {
+        return Err(
+            "Filters can't be used in sandbox mode: they run with full filesystem access"
+                .to_string(),
+        );
+    }
+
+    if extra_args
+        .iter()
+        .any(|arg| arg.starts_with("--resource-path"))
+    {
+        return Err(
+            "--resource-path can't be used in sandbox mode: it reads files outside the input"
+                .to_string(),
+        );
+    }
+
+    Ok(())
+}
+
+/// Get the base name of a pandoc format spec, e.g. "markdown" for "markdown+hard_line_breaks-smart"
+fn base_format(format: &str) -> &str {
+    format.split(['+', '-']).next().unwrap_or(format)
+}
+
+/// Minimum pandoc version for options and formats that older releases reject
+const FEATURE_MIN_VERSIONS: &[(&str, &str)] = &[
+    ("--defaults", "2.8"),
+    ("--citeproc", "2.11"),
+    ("--sandbox", "2.15"),
+    ("--split-level", "3.0"),
+    ("chunkedhtml output", "3.0"),
+    ("djot input", "3.1.12"),
+    ("djot output", "3.1.12"),
+    ("typst output", "3.0"),
+    ("typst input", "3.1.2"),
+];
+
+/// Get the version of a pandoc executable, None if it can't be determined
+fn detect_pandoc_version(pandoc_cmd: &str) -> Option<String> {
+    crate::utils::create_hidden_command(pandoc_cmd)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_version(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Check that the pandoc in use is recent enough for every requested feature
+/// An undetectable or non-numeric version is let through so pandoc decides
+fn check_feature_support(pandoc_cmd: &str, features: &[String]) -> Result<(), String> {
+    let required: Vec<(&str, &str)> = FEATURE_MIN_VERSIONS
+        .iter()
+        .filter(|(feature, _)| features.iter().any(|f| f == feature))
+        .copied()
+        .collect();
+    if required.is_empty() {
+        return Ok(());
+    }
+
+    let Some(version) = detect_pandoc_version(pandoc_cmd) else {
+        return Ok(());
+    };
+
+    for (feature, min_version) in required {
+        if compare_versions(&version, min_version) == Some(std::cmp::Ordering::Less) {
+            return Err(format!(
+                "{} requires pandoc >= {}, you have {}",
+                feature, min_version, version
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that the pandoc in use was built with Lua, which Lua filters need
+/// Versions that don't report their features are let through so pandoc decides
+fn check_lua_support(pandoc_cmd: &str) -> Result<(), String> {
+    let features = crate::utils::create_hidden_command(pandoc_cmd)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| parse_compiled_features(&String::from_utf8_lossy(&output.stdout)));
+
+    match features {
+        Some(features) if !features.iter().any(|f| f == "lua") => Err(format!(
+            "Lua filters are not supported: pandoc at '{}' was built without Lua",
+            pandoc_cmd
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Check that the base format of a format spec is supported; custom Lua readers/writers pass through
+fn validate_format_spec(format: &str, supported: &[String], kind: &str) -> Result<(), String> {
+    if format.ends_with(".lua") {
+        return Ok(());
+    }
+
+    let base = base_format(format);
+    if base.is_empty() || !supported.iter().any(|f| f == base) {
+        return Err(format!(
+            "{} format '{}' is not supported by this Pandoc installation",
+            kind, base
+        ));
+    }
+
+    Ok(())
+}
+
+/// Convert a text snippet (e.g. a selection or live preview buffer) and return the result
+/// Formats accept pandoc's extension syntax, e.g. "markdown+hard_line_breaks-smart"
+#[tauri::command]
+pub async fn convert_text(
+    text: String,
+    input_format: String,
+    output_format: String,
+    custom_path: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let pandoc_cmd = if let Some(custom_path) = custom_path {
+        custom_path
+    } else {
+        find_pandoc_with_priority(&app_handle)?
+    };
+
+    convert_text_with(&pandoc_cmd, &text, &input_format, &output_format)
+}
+
+/// Convert a text snippet with the given pandoc executable (see `convert_text`)
+fn convert_text_with(
+    pandoc_cmd: &str,
+    text: &str,
+    input_format: &str,
+    output_format: &str,
+) -> Result<String, String> {
+    let (supported_inputs, supported_outputs) = get_supported_formats(pandoc_cmd)?;
+    validate_format_spec(input_format, &supported_inputs, "Input")?;
+    validate_format_spec(output_format, &supported_outputs, "Output")?;
+
+    let mut child = crate::utils::create_hidden_command(pandoc_cmd)
+        .args(["-f", input_format, "-t", output_format])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute pandoc at '{}': {}", pandoc_cmd, e))?;
+
+    // Pandoc reads all of its input before writing, so this can't deadlock on stdout
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("Failed to send text to pandoc: {}", e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to wait for pandoc: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        Err(format!("Pandoc conversion failed: {}", error_msg.trim()))
+    }
+}
+
+/// Convert the text on the system clipboard, e.g. pasted markdown to HTML
+/// With `write_back` (the default) the result also replaces the clipboard contents
+/// Binary output formats can't be put on the clipboard and are rejected
+#[tauri::command]
+pub async fn convert_clipboard(
+    input_format: String,
+    output_format: String,
+    write_back: Option<bool>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    if BINARY_OUTPUT_FORMATS.contains(&base_format(&output_format)) {
+        return Err(format!(
+            "Output format '{}' is binary and can't be placed on the clipboard",
+            output_format
+        ));
+    }
+
+    let text = app_handle
+        .clipboard()
+        .read_text()
+        .map_err(|e| format!("Failed to read clipboard: {}", e))?;
+    if text.trim().is_empty() {
+        return Err("Clipboard does not contain any text".to_string());
+    }
+
+    let converted =
+        convert_text(text, input_format, output_format, None, app_handle.clone()).await?;
+
+    if write_back.unwrap_or(true) {
+        app_handle
+            .clipboard()
+            .write_text(converted.clone())
+            .map_err(|e| format!("Failed to write clipboard: {}", e))?;
+    }
+
+    Ok(converted)
+}
+
+/// Input formats that fit a file extension, None for extensions without a clear format
+fn input_formats_for_extension(extension: &str) -> Option<&'static [&'static str]> {
+    let formats: &[&str] = match extension {
+        "md" | "markdown" | "mdown" | "mkd" | "mkdn" => &[
+            "markdown",
+            "markdown_strict",
+            "markdown_phpextra",
+            "markdown_mmd",
+            "gfm",
+            "commonmark",
+            "commonmark_x",
+        ],
+        "html" | "htm" | "xhtml" => &["html"],
+        "tex" | "latex" | "ltx" => &["latex"],
+        "rst" => &["rst"],
+        "org" => &["org"],
+        "docx" => &["docx"],
+        "odt" => &["odt"],
+        "epub" => &["epub"],
+        "ipynb" => &["ipynb"],
+        "typ" => &["typst"],
+        "dj" | "djot" => &["djot"],
+        "textile" => &["textile"],
+        "rtf" => &["rtf"],
+        "csv" => &["csv"],
+        "tsv" => &["tsv"],
+        "bib" => &["bibtex", "biblatex"],
+        "opml" => &["opml"],
+        "fb2" => &["fb2"],
+        "json" => &["json", "csljson"],
+        _ => return None,
+    };
+    Some(formats)
+}
+
+/// Describe a mismatch between an explicitly chosen input format and the file extension,
+/// e.g. "latex" selected for a .md file; such conversions succeed with garbled output
+fn input_format_mismatch(input_file: &Path, input_format: &str) -> Option<String> {
+    let extension = input_file.extension()?.to_str()?.to_lowercase();
+    let expected = input_formats_for_extension(&extension)?;
+    let format = base_format(input_format);
+
+    // Custom Lua readers can read anything
+    if input_format.ends_with(".lua") || expected.contains(&format) {
+        return None;
+    }
+
+    Some(format!(
+        "you selected {} but the file is .{} (usually {})",
+        format, extension, expected[0]
+    ))
+}
+
+/// Usual file extension for an output format, e.g. "tex" for latex
+fn output_extension(output_format: &str) -> &str {
+    match base_format(output_format) {
+        "latex" | "beamer" | "context" => "tex",
+        "markdown" | "markdown_strict" | "markdown_phpextra" | "markdown_mmd" | "gfm"
+        | "commonmark" | "commonmark_x" => "md",
+        "html" | "html4" | "html5" | "revealjs" | "slidy" | "slideous" | "s5" | "dzslides" => {
+            "html"
+        }
+        "plain" => "txt",
+        "asciidoc" | "asciidoctor" => "adoc",
+        "docbook" | "docbook4" | "docbook5" | "jats" | "tei" => "xml",
+        "mediawiki" | "dokuwiki" => "wiki",
+        "native" => "hs",
+        "epub2" | "epub3" => "epub",
+        other => other,
+    }
+}
+
+/// Collect the plain text of a JSON AST inline list (e.g. a heading's content)
+fn inline_text(value: &serde_json::Value, text: &mut String) {
+    match value {
+        serde_json::Value::Array(items) => items.iter().for_each(|item| inline_text(item, text)),
+        serde_json::Value::Object(node) => match node.get("t").and_then(|t| t.as_str()) {
+            Some("Str") => text.push_str(node.get("c").and_then(|c| c.as_str()).unwrap_or("")),
+            Some("Space" | "SoftBreak" | "LineBreak") => text.push(' '),
+            // Code and Math carry [attributes or math type, text]
+            Some("Code" | "Math") => {
+                if let Some(code) = node
+                    .get("c")
+                    .and_then(|c| c.get(1))
+                    .and_then(|c| c.as_str())
+                {
+                    text.push_str(code);
+                }
+            }
+            _ => {
+                if let Some(content) = node.get("c") {
+                    inline_text(content, text);
+                }
+            }
+        },
+        _ => {}
+    }
+}
+
+/// Turn heading text into a safe file name stem
+fn sanitize_file_stem(text: &str) -> String {
+    let stem: String = text
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    let stem = stem
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if stem.is_empty() {
+        "section".to_string()
+    } else {
+        stem.chars().take(60).collect()
+    }
+}
+
+/// Convert a document into one output file per heading of the given level (1 = chapters)
+/// Files are named from the heading text ("01-introduction.docx"); content before the first
+/// heading goes into "00-preamble". Returns the produced paths in document order
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn convert_split_by_heading(
+    input_file: String,
+    input_format: Option<String>,
+    output_format: String,
+    output_dir: String,
+    level: u32,
+    custom_pandoc_path: Option<String>,
+    pdf_engine: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<String>, String> {
+    if level == 0 {
+        return Err("Heading level must be at least 1".to_string());
+    }
+
+    let pandoc_cmd = if let Some(custom_path) = custom_pandoc_path {
+        custom_path
+    } else {
+        find_pandoc_with_priority(&app_handle)?
+    };
+
+    let input_format = input_format.filter(|fmt| !fmt.is_empty() && fmt != "auto");
+    let json = run_pandoc_to_string(&pandoc_cmd, &input_file, input_format.as_deref(), "json")?;
+    let ast: serde_json::Value = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse pandoc JSON AST: {}", e))?;
+
+    let blocks = ast
+        .get("blocks")
+        .and_then(|b| b.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    // Split the blocks into (title, blocks) sections at each heading of the requested level
+    let mut sections: Vec<(String, Vec<serde_json::Value>)> =
+        vec![("preamble".to_string(), vec![])];
+    for block in blocks {
+        let heading = (block.get("t").and_then(|t| t.as_str()) == Some("Header"))
+            .then(|| block.get("c"))
+            .flatten()
+            .filter(|c| c.get(0).and_then(|l| l.as_u64()) == Some(level as u64));
+
+        if let Some(content) = heading {
+            let mut title = String::new();
+            if let Some(inlines) = content.get(2) {
+                inline_text(inlines, &mut title);
+            }
+            sections.push((title, vec![block]));
+        } else if let Some((_, section_blocks)) = sections.last_mut() {
+            section_blocks.push(block);
+        }
+    }
+
+    let output_dir = PathBuf::from(output_dir);
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output directory: {}", e))?;
+
+    let extension = output_extension(&output_format);
+    let mut produced = Vec::new();
+    for (index, (title, section_blocks)) in sections.into_iter().enumerate() {
+        if section_blocks.is_empty() {
+            continue;
+        }
+
+        let mut section_ast = ast.clone();
+        section_ast["blocks"] = serde_json::Value::Array(section_blocks);
+
+        let file_name = format!("{:02}-{}.{}", index, sanitize_file_stem(&title), extension);
+        let output_file = output_dir.join(file_name).to_string_lossy().to_string();
+
+        from_json_ast(
+            section_ast,
+            output_file.clone(),
+            output_format.clone(),
+            Some(pandoc_cmd.clone()),
+            pdf_engine.clone(),
+            app_handle.clone(),
+        )
+        .await?;
+        produced.push(output_file);
+    }
+
+    Ok(produced)
+}
+
+/// Running conversions: cancel handles keyed by the frontend's conversion id,
+/// plus the process id of every running conversion so shutdown can kill them all
+#[derive(Default)]
+pub struct ConversionRegistry {
+    cancel_senders: Mutex<HashMap<String, oneshot::Sender<()>>>,
+    pids: Mutex<HashSet<u32>>,
+}
+
+/// Cancel a running conversion started with the given `conversion_id`
+#[tauri::command]
+pub fn cancel_conversion(
+    conversion_id: String,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    let registry = app_handle.state::<ConversionRegistry>();
+    let sender = registry
+        .cancel_senders
+        .lock()
+        .map_err(|_| "Conversion registry is unavailable".to_string())?
+        .remove(&conversion_id)
+        .ok_or_else(|| format!("No running conversion with id '{}'", conversion_id))?;
+
+    // The conversion may have finished in the meantime, which is fine
+    let _ = sender.send(());
+    Ok(())
+}
+
+/// Cancel every running conversion and kill its process tree
+/// Returns the number of processes killed
+fn kill_all_conversions(app_handle: &tauri::AppHandle) -> usize {
+    let Some(registry) = app_handle.try_state::<ConversionRegistry>() else {
+        return 0;
+    };
 
-    let output = crate::utils::create_hidden_command(&pandoc_cmd)
-        .args(&args)
-        .current_dir(&working_dir)
+    // Let cancellable conversions clean up their partial output while the runtime is alive
+    if let Ok(mut running) = registry.cancel_senders.lock() {
+        for (_, sender) in running.drain() {
+            let _ = sender.send(());
+        }
+    }
+
+    let pids: Vec<u32> = match registry.pids.lock() {
+        Ok(mut pids) => pids.drain().collect(),
+        Err(_) => return 0,
+    };
+    for pid in &pids {
+        kill_process_tree(*pid);
+    }
+
+    pids.len()
+}
+
+/// Stop all conversions, watches and downloads and remove leftover download directories
+/// Runs on app exit; log files are written unbuffered, so there is nothing to flush
+pub fn cleanup_active_operations(app_handle: &tauri::AppHandle) -> String {
+    let conversions = kill_all_conversions(app_handle);
+    let watches = crate::watch::stop_all_watches(app_handle);
+    let downloads = crate::manager::cancel_all_downloads(app_handle.clone());
+    let removed_dirs = crate::manager::remove_leftover_downloads(app_handle);
+
+    let summary = format!(
+        "Stopped {} conversions, {} watches and {} downloads, removed {} download directories",
+        conversions, watches, downloads, removed_dirs
+    );
+    tracing::info!("Shutdown cleanup: {}", summary);
+    summary
+}
+
+/// Cancel and clean up all active operations, e.g. before the window closes
+#[tauri::command]
+pub async fn shutdown_cleanup(app_handle: tauri::AppHandle) -> String {
+    cleanup_active_operations(&app_handle)
+}
+
+/// Remove a conversion from the registry once it has finished
+fn unregister_conversion(app_handle: &tauri::AppHandle, conversion_id: &str) {
+    if let Some(registry) = app_handle.try_state::<ConversionRegistry>() {
+        if let Ok(mut running) = registry.cancel_senders.lock() {
+            running.remove(conversion_id);
+        }
+    }
+}
+
+/// Run a pandoc command, optionally emitting each stderr line as a `conversion-log` event
+/// With a `conversion_id`, the run can be stopped through `cancel_conversion`,
+/// in which case `Ok(None)` is returned after the process tree has been killed
+async fn run_cancellable(
+    command: Command,
+    conversion_id: Option<&str>,
+    stream_log: bool,
+    app_handle: &tauri::AppHandle,
+) -> std::io::Result<Option<Output>> {
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+
+    let mut command = tokio::process::Command::from(command);
+    command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let mut child = command.spawn()?;
+    let pid = child.id();
+
+    let (cancel_tx, cancel_rx) = oneshot::channel();
+    if let Some(registry) = app_handle.try_state::<ConversionRegistry>() {
+        if let (Some(id), Ok(mut running)) = (conversion_id, registry.cancel_senders.lock()) {
+            running.insert(id.to_string(), cancel_tx);
+        }
+        if let (Some(pid), Ok(mut pids)) = (pid, registry.pids.lock()) {
+            pids.insert(pid);
+        }
+    }
+
+    let stdout_pipe = child.stdout.take();
+    let stderr_pipe = child.stderr.take();
+
+    let run = async {
+        let read_stdout = async {
+            let mut stdout = Vec::new();
+            if let Some(mut pipe) = stdout_pipe {
+                pipe.read_to_end(&mut stdout).await?;
+            }
+            Ok::<_, std::io::Error>(stdout)
+        };
+
+        let read_stderr = async {
+            let mut stderr = Vec::new();
+            if let Some(pipe) = stderr_pipe {
+                // Raw bytes: warnings may be in a Windows code page or name non-UTF-8 paths
+                let mut reader = tokio::io::BufReader::new(pipe);
+                let mut line = Vec::new();
+                while reader.read_until(b'\n', &mut line).await? > 0 {
+                    if stream_log {
+                        let text = String::from_utf8_lossy(&line);
+                        let _ =
+                            app_handle.emit("conversion-log", text.trim_end_matches(['\r', '\n']));
+                    }
+                    stderr.append(&mut line);
+                }
+            }
+            Ok::<_, std::io::Error>(stderr)
+        };
+
+        let (stdout, stderr) = tokio::try_join!(read_stdout, read_stderr)?;
+        let status = child.wait().await?;
+
+        Ok::<_, std::io::Error>(Output {
+            status,
+            stdout,
+            stderr,
+        })
+    };
+
+    // A dropped sender (no id, or already unregistered) just disables the cancel branch
+    let result = tokio::select! {
+        output = run => output.map(Some),
+        Ok(()) = cancel_rx => {
+            if let Some(pid) = pid {
+                kill_process_tree(pid);
+            }
+            Ok(None)
+        }
+    };
+
+    if let Some(id) = conversion_id {
+        unregister_conversion(app_handle, id);
+    }
+    if let (Some(pid), Some(registry)) = (pid, app_handle.try_state::<ConversionRegistry>()) {
+        if let Ok(mut pids) = registry.pids.lock() {
+            pids.remove(&pid);
+        }
+    }
+
+    result
+}
+
+/// Kill a process together with the helpers it spawned (PDF engines, filters)
+fn kill_process_tree(pid: u32) {
+    #[cfg(unix)]
+    {
+        // create_hidden_command makes the child a process group leader, so its pid is the group id
+        unsafe {
+            libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        let _ = crate::utils::create_hidden_command("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .output();
+    }
+}
+
+/// Fail for the headless browser engine where only pandoc's own PDF engines work
+fn reject_browser_engine(engine: &str) -> Result<(), String> {
+    if engine == HEADLESS_BROWSER_ENGINE {
+        return Err(
+            "The headless browser PDF engine only supports file conversions; \
+             install a PDF engine such as typst or wkhtmltopdf"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Print an HTML file to PDF with a headless Chromium-based browser
+fn print_html_to_pdf(browser: &Path, html_file: &Path, output_file: &Path) -> Result<(), String> {
+    let mut print_arg = std::ffi::OsString::from("--print-to-pdf=");
+    print_arg.push(output_file);
+
+    let output = crate::utils::create_hidden_command(browser)
+        .args(["--headless", "--disable-gpu", "--no-pdf-header-footer"])
+        .arg(print_arg)
+        .arg(html_file)
         .output()
-        .map_err(|e| format!("Failed to execute pandoc at '{}': {}", pandoc_cmd, e))?;
+        .map_err(|e| format!("Failed to run browser at '{}': {}", browser.display(), e))?;
 
-    if output.status.success() {
-        Ok(format!(
-            "Successfully converted {} to {}",
-            input_file, output_file
-        ))
+    // Chrome may exit successfully without writing anything, e.g. for an unreadable input
+    if !output.status.success() || !output_file.is_file() {
+        return Err(format!(
+            "Printing to PDF with {} failed: {}",
+            browser.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Extra pandoc arguments for the typst PDF engine: the configured package cache directory
+/// Empty for other engines or when no directory is configured
+fn typst_engine_args(engine: &str, app_handle: &tauri::AppHandle) -> Result<Vec<String>, String> {
+    let is_typst = Path::new(engine)
+        .file_stem()
+        .is_some_and(|stem| stem.eq_ignore_ascii_case("typst"));
+    if !is_typst {
+        return Ok(Vec::new());
+    }
+
+    let Some(package_path) = crate::config::load_config(app_handle).typst_package_path else {
+        return Ok(Vec::new());
+    };
+    if !Path::new(&package_path).is_dir() {
+        return Err(format!(
+            "Typst package cache directory not found: {}",
+            package_path
+        ));
+    }
+
+    Ok(vec![format!(
+        "--pdf-engine-opt=--package-cache-path={}",
+        package_path
+    )])
+}
+
+/// HTML-based output formats that can embed their resources
+const HTML_OUTPUT_FORMATS: &[&str] = &[
+    "html", "html4", "html5", "revealjs", "slidy", "slideous", "s5", "dzslides",
+];
+
+/// First pandoc version whose `--embed-resources` replaces the deprecated `--self-contained`
+const EMBED_RESOURCES_VERSION: &str = "3.1.7";
+
+/// Arguments for a standalone HTML file with all resources inlined
+/// Older pandoc versions only understand `--self-contained`
+fn embed_resources_args(pandoc_cmd: &str) -> Vec<String> {
+    let is_old = detect_pandoc_version(pandoc_cmd).is_some_and(|version| {
+        compare_versions(&version, EMBED_RESOURCES_VERSION) == Some(std::cmp::Ordering::Less)
+    });
+
+    if is_old {
+        vec!["--self-contained".to_string()]
     } else {
-        let error_msg =
-            String::from_utf8(output.stderr).unwrap_or_else(|_| "Unknown pandoc error".to_string());
+        vec!["--embed-resources".to_string(), "--standalone".to_string()]
+    }
+}
 
-        // Provide helpful error messages for common PDF issues
-        if output_format == "pdf" && error_msg.contains("not found") {
+/// Output formats written as binary files (never line-ending normalized)
+const BINARY_OUTPUT_FORMATS: &[&str] = &[
+    "docx",
+    "pptx",
+    "odt",
+    "epub",
+    "epub2",
+    "epub3",
+    "pdf",
+    "chunkedhtml",
+];
+
+/// Resolve a line ending option ("lf", "crlf", "native") to the actual sequence
+/// Defaults to "native", the host platform convention
+fn resolve_line_ending(line_ending: Option<&str>) -> Result<&'static str, String> {
+    match line_ending.unwrap_or("native").to_lowercase().as_str() {
+        "lf" => Ok("\n"),
+        "crlf" => Ok("\r\n"),
+        "native" | "" => Ok(if cfg!(windows) { "\r\n" } else { "\n" }),
+        other => Err(format!(
+            "Invalid line ending '{}', expected 'lf', 'crlf' or 'native'",
+            other
+        )),
+    }
+}
+
+/// Rewrite a text output file with the requested line ending
+fn normalize_line_endings(path: &Path, line_ending: &str) -> Result<(), String> {
+    // Skip output that isn't valid UTF-8 text (or isn't a single file)
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Ok(());
+    };
+
+    let normalized = content.replace("\r\n", "\n");
+    let normalized = if line_ending == "\n" {
+        normalized
+    } else {
+        normalized.replace('\n', line_ending)
+    };
+
+    if normalized != content {
+        std::fs::write(path, normalized)
+            .map_err(|e| format!("Failed to normalize line endings: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Output formats that accept a --reference-doc
+const REFERENCE_DOC_FORMATS: &[&str] = &["docx", "pptx", "odt"];
+
+/// Validate a reference document against the requested output format
+fn validate_reference_doc(
+    reference_doc: &str,
+    output_format: &str,
+    working_dir: &Path,
+) -> Result<(), String> {
+    if !REFERENCE_DOC_FORMATS.contains(&output_format) {
+        return Err(format!(
+            "Reference documents are only supported for {} output, not '{}'",
+            REFERENCE_DOC_FORMATS.join(", "),
+            output_format
+        ));
+    }
+
+    let path = resolve_option_path(working_dir, reference_doc);
+    if !path.is_file() {
+        return Err(format!("Reference document not found: {}", reference_doc));
+    }
+
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    if extension != output_format {
+        return Err(format!(
+            "Reference document '{}' must be a .{} file for {} output",
+            reference_doc, output_format, output_format
+        ));
+    }
+
+    Ok(())
+}
+
+/// Build --citeproc, --bibliography and --csl arguments after validating the files
+fn build_citation_args(
+    bibliography: &[String],
+    csl: Option<&str>,
+    working_dir: &Path,
+) -> Result<Vec<String>, String> {
+    let mut args = vec!["--citeproc".to_string()];
+
+    for file in bibliography {
+        if !resolve_option_path(working_dir, file).is_file() {
+            return Err(format!("Bibliography file not found: {}", file));
+        }
+        args.push(format!("--bibliography={}", file));
+    }
+
+    if let Some(csl) = csl.filter(|c| !c.is_empty()) {
+        let path = resolve_option_path(working_dir, csl);
+        if !path.is_file() {
+            return Err(format!("CSL style file not found: {}", csl));
+        }
+
+        let is_csl = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("csl"));
+        if !is_csl {
+            return Err(format!("CSL style '{}' must have a .csl extension", csl));
+        }
+
+        args.push(format!("--csl={}", csl));
+    }
+
+    Ok(args)
+}
+
+/// Select the PDF engine to use: the user's choice if available, otherwise the best default
+fn select_pdf_engine(
+    output_format: &str,
+    pdf_engine: Option<String>,
+    app_handle: &tauri::AppHandle,
+) -> Result<String, String> {
+    let available_engines = check_available_pdf_engines_for_format(output_format, app_handle);
+    let pdf_engine = pdf_engine.map(|engine| {
+        if engine.eq_ignore_ascii_case("chromium") {
+            HEADLESS_BROWSER_ENGINE.to_string()
+        } else {
+            engine
+        }
+    });
+
+    // Use user-specified engine or determine best default for output format
+    if let Some(user_engine) = pdf_engine {
+        // Validate that the user-specified engine is available
+        if available_engines.contains(&user_engine) {
+            Ok(user_engine)
+        } else {
             Err(format!(
-                "PDF conversion failed: Required engine not found.\n\n\
-                Solutions:\n\
-                1. Install the recommended PDF engine for this format\n\
-                2. Alternative: Convert to HTML first, then print to PDF from browser\n\
-                3. Try a different output format (html, docx, etc.)\n\n\
-                Original error: {}",
-                error_msg.trim()
+                "Specified PDF engine '{}' is not available.\n\
+                 Available engines for PDF output: {}",
+                user_engine,
+                available_engines.join(", ")
             ))
+        }
+    } else {
+        // Use the configured preference if it's available
+        let preferred = crate::config::load_config(app_handle).preferred_pdf_engine;
+        if let Some(engine) = preferred.and_then(|p| find_engine(&available_engines, &p)) {
+            return Ok(engine);
+        }
+
+        // Auto-select best available engine for this output format
+        let default_engine = get_default_pdf_engine(output_format);
+
+        // Try default engine first, then fallback to any available
+        if available_engines.contains(&default_engine.to_string()) {
+            Ok(default_engine.to_string())
+        } else if let Some(engine) = available_engines.first() {
+            Ok(engine.clone())
         } else {
-            Err(format!("Pandoc conversion failed: {}", error_msg.trim()))
+            Err(format!(
+                "PDF conversion failed: No PDF engine found for output format '{}'.\n\n\
+                 Recommended PDF engines for {}:\n\
+                 {}\n\n\
+                 Installation guides:\n\
+                 • typst: 'cargo install typst-cli' or download from GitHub releases\n\
+                 • wkhtmltopdf: Download from https://wkhtmltopdf.org/\n\
+                 • weasyprint: 'pip install weasyprint'\n\
+                 • LaTeX distribution (TeX Live, MiKTeX): For academic publishing\n\n\
+                 Alternatively, try converting to HTML first, then use a browser to print to PDF.",
+                output_format,
+                output_format,
+                get_pdf_engines_for_format(output_format).join(", ")
+            ))
         }
     }
 }
 
+/// Find an engine by name among available engines (which may be full paths, e.g. bundled typst)
+fn find_engine(available_engines: &[String], name: &str) -> Option<String> {
+    available_engines
+        .iter()
+        .find(|engine| {
+            engine.as_str() == name
+                || Path::new(engine).file_stem().and_then(|s| s.to_str()) == Some(name)
+        })
+        .cloned()
+}
+
+/// Check if a PDF engine is available for PDF output
+pub fn is_pdf_engine_available(engine: &str, app_handle: &tauri::AppHandle) -> bool {
+    find_engine(
+        &check_available_pdf_engines_for_format("pdf", app_handle),
+        engine,
+    )
+    .is_some()
+}
+
 /// Get available PDF engines for specific output format
 #[tauri::command]
 pub async fn get_available_pdf_engines(
@@ -568,6 +3511,14 @@ fn check_available_pdf_engines_for_format(
     let engines = get_pdf_engines_for_format(output_format);
 
     for engine in engines {
+        // The browser engine isn't a CLI with --version; it's found by location
+        if engine == HEADLESS_BROWSER_ENGINE {
+            if crate::utils::find_chromium_browser().is_some() {
+                available.push(engine.to_string());
+            }
+            continue;
+        }
+
         // Check bundled engines first (currently only typst)
         if engine == "typst" {
             if let Some(bundled_typst) = get_best_typst_path(app_handle) {
@@ -598,6 +3549,156 @@ fn check_available_pdf_engines_for_format(
     available
 }
 
+/// Detect installed TeX distributions (TeX Live, MiKTeX, Tectonic)
+/// Explains why LaTeX-based PDF engines are or aren't available
+#[tauri::command]
+pub async fn detect_latex_distributions() -> Vec<LatexInfo> {
+    let mut distributions = Vec::new();
+
+    // TeX Live identifies itself in `tex --version`, e.g. "TeX 3.141592653 (TeX Live 2023/Debian)"
+    if let Some(first_line) = get_version_line("tex") {
+        if let Some(start) = first_line.find("TeX Live") {
+            let version = first_line[start + "TeX Live".len()..]
+                .trim_start()
+                .split(|c: char| c == ')' || c == '/' || c.is_whitespace())
+                .next()
+                .unwrap_or("")
+                .to_string();
+
+            distributions.push(LatexInfo {
+                name: "TeX Live".to_string(),
+                version,
+                path: crate::utils::find_in_path("tex").ok(),
+            });
+        }
+    }
+
+    if let Some(miktex) = detect_miktex() {
+        distributions.push(miktex);
+    }
+
+    // "Tectonic 0.15.0"
+    if let Some(first_line) = get_version_line("tectonic") {
+        distributions.push(LatexInfo {
+            name: "Tectonic".to_string(),
+            version: parse_version(first_line.trim_start_matches("Tectonic")),
+            path: crate::utils::find_in_path("tectonic").ok(),
+        });
+    }
+
+    distributions
+}
+
+/// Detect MiKTeX from its CLI, falling back to the registry on Windows
+fn detect_miktex() -> Option<LatexInfo> {
+    // "miktex version 4.8 ... MiKTeX 23.10"
+    if let Some(first_line) = get_version_line("miktex") {
+        let version = first_line
+            .find("MiKTeX")
+            .map(|start| parse_version(&first_line[start + "MiKTeX".len()..]))
+            .unwrap_or_else(|| parse_version(first_line.trim_start_matches("miktex")));
+
+        return Some(LatexInfo {
+            name: "MiKTeX".to_string(),
+            version,
+            path: crate::utils::find_in_path("miktex").ok(),
+        });
+    }
+
+    // Installs that never added MiKTeX to PATH are still registered
+    if cfg!(target_os = "windows") {
+        for hive in ["HKCU", "HKLM"] {
+            let output = crate::utils::create_hidden_command("reg")
+                .args([
+                    "query",
+                    &format!("{}\\Software\\MiKTeX.org\\MiKTeX", hive),
+                    "/s",
+                ])
+                .output()
+                .ok()?;
+
+            if output.status.success() {
+                return Some(LatexInfo {
+                    name: "MiKTeX".to_string(),
+                    version: "Unknown".to_string(),
+                    path: None,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Run `<program> --version` and return the first non-empty line of its output
+fn get_version_line(program: &str) -> Option<String> {
+    let output = crate::utils::create_hidden_command(program)
+        .arg("--version")
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// List the font families a PDF engine can use, sorted and deduplicated
+/// Typst fonts come from `typst fonts`; xelatex/lualatex use fontconfig, or luaotfload as a fallback
+#[tauri::command]
+pub async fn list_available_fonts(engine: String, app_handle: tauri::AppHandle) -> Vec<String> {
+    let lines = match engine.as_str() {
+        "typst" => get_best_typst_path(&app_handle)
+            .and_then(|typst| run_for_lines(&typst, &["fonts"]))
+            .unwrap_or_default(),
+        "xelatex" | "lualatex" => run_for_lines("fc-list", &[":", "family"])
+            .or_else(|| {
+                run_for_lines(
+                    "luaotfload-tool",
+                    &["--list=familyname", "--fields=familyname"],
+                )
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+
+    // fc-list joins localized names with commas, e.g. "Noto Sans CJK SC,Noto Sans CJK SC Regular"
+    let families: std::collections::BTreeSet<String> = lines
+        .iter()
+        .flat_map(|line| line.split(','))
+        .map(|family| family.trim().to_string())
+        .filter(|family| !family.is_empty())
+        .collect();
+
+    families.into_iter().collect()
+}
+
+/// Run a program and return its non-empty stdout lines, or None if it isn't available or fails
+fn run_for_lines(program: &str, args: &[&str]) -> Option<Vec<String>> {
+    let output = crate::utils::create_hidden_command(program)
+        .args(args)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
 /// Get the best available typst path (bundled or system) - returns full path when possible
 fn get_best_typst_path(app_handle: &tauri::AppHandle) -> Option<String> {
     // Check bundled typst in multiple possible locations
@@ -606,12 +3707,17 @@ fn get_best_typst_path(app_handle: &tauri::AppHandle) -> Option<String> {
     // Try different possible resource directories
     let mut possible_resource_dirs = Vec::new();
 
-    // 1. Try the official resource directory (works in production)
+    // 1. Try the app data directory (updates installed when resources aren't writable)
+    if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
+        possible_resource_dirs.push(app_data_dir);
+    }
+
+    // 2. Try the official resource directory (works in production)
     if let Ok(resource_dir) = app_handle.path().resource_dir() {
         possible_resource_dirs.push(resource_dir);
     }
 
-    // 2. Try relative to the current working directory (development)
+    // 3. Try relative to the current working directory (development)
     if let Ok(current_dir) = std::env::current_dir() {
         // If we're already in src-tauri directory, don't add it again
         let resources_path = if current_dir.file_name() == Some(std::ffi::OsStr::new("src-tauri")) {
@@ -622,7 +3728,7 @@ fn get_best_typst_path(app_handle: &tauri::AppHandle) -> Option<String> {
         possible_resource_dirs.push(resources_path);
     }
 
-    // 3. Try relative to the executable directory
+    // 4. Try relative to the executable directory
     if let Ok(exe_path) = std::env::current_exe() {
         if let Some(exe_parent) = exe_path.parent() {
             possible_resource_dirs.push(exe_parent.join("resources"));
@@ -635,7 +3741,7 @@ fn get_best_typst_path(app_handle: &tauri::AppHandle) -> Option<String> {
         // Try direct path first
         let direct_path = typst_dir.join(&exe_name);
         if direct_path.exists() {
-            if validate_typst_executable(&direct_path.to_string_lossy()) {
+            if validate_typst_executable(&direct_path) {
                 let path_str = direct_path.to_string_lossy().to_string();
                 return Some(path_str);
             }
@@ -649,7 +3755,7 @@ fn get_best_typst_path(app_handle: &tauri::AppHandle) -> Option<String> {
                         let nested_typst = entry.path().join(&exe_name);
 
                         if nested_typst.exists() {
-                            if validate_typst_executable(&nested_typst.to_string_lossy()) {
+                            if validate_typst_executable(&nested_typst) {
                                 let path_str = nested_typst.to_string_lossy().to_string();
                                 return Some(path_str);
                             }
@@ -678,58 +3784,11 @@ fn get_best_typst_path(app_handle: &tauri::AppHandle) -> Option<String> {
     }
 }
 
-/// Extract clean version number from version string
-fn extract_version_number(version_str: &str) -> String {
-    // Handle common format: "pandoc.exe 3.7.0.2" or "pandoc 3.7.0.2"
-    let parts: Vec<&str> = version_str.trim().split_whitespace().collect();
-
-    // Look for version number in the parts
-    for part in &parts {
-        // Skip program names
-        if part.contains("pandoc") {
-            continue;
-        }
-
-        // Check if this part looks like a version number
-        if part.chars().next().map_or(false, |c| c.is_ascii_digit()) {
-            // Extract version pattern (digits and dots)
-            let mut version = String::new();
-            for ch in part.chars() {
-                if ch.is_ascii_digit() || ch == '.' {
-                    version.push(ch);
-                } else {
-                    break;
-                }
-            }
-
-            if !version.is_empty() && version != "." {
-                return version;
-            }
-        }
-    }
-
-    // Fallback: try to extract from the whole string
-    let cleaned = version_str
-        .trim()
-        .replace("pandoc.exe", "")
-        .replace("pandoc", "")
-        .trim()
-        .trim_start_matches('v')
-        .trim()
-        .to_string();
-
-    if !cleaned.is_empty() {
-        cleaned
-    } else {
-        "Unknown".to_string()
-    }
-}
-
 /// Legacy function for compatibility - returns clean version number
 #[tauri::command]
 pub async fn check_pandoc_version() -> Result<String, String> {
     let info = get_pandoc_info(None).await?;
-    Ok(extract_version_number(&info.version))
+    Ok(parse_version(&info.version))
 }
 
 /// Setup managed Pandoc from build-time resources (legacy function kept for compatibility)
@@ -768,11 +3827,80 @@ pub async fn get_bundled_typst_path(app_handle: tauri::AppHandle) -> Result<Stri
     get_best_typst_path(&app_handle).ok_or_else(|| "Typst executable not found".to_string())
 }
 
+/// Get the version of the best available typst, None if there is none
+pub fn get_typst_version(app_handle: &tauri::AppHandle) -> Option<String> {
+    let typst_path = get_best_typst_path(app_handle)?;
+    crate::utils::create_hidden_command(&typst_path)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_version(&String::from_utf8_lossy(&output.stdout)))
+}
+
 /// Validate if a Typst executable is working
-fn validate_typst_executable(path: &str) -> bool {
-    crate::utils::create_hidden_command(path)
+fn validate_typst_executable<P: AsRef<Path>>(path: P) -> bool {
+    let works = crate::utils::create_hidden_command(path.as_ref())
         .arg("--version")
         .output()
         .map(|output| output.status.success())
-        .unwrap_or(false)
+        .unwrap_or(false);
+
+    if !works {
+        if let Some(message) = crate::utils::quarantine_error(&path) {
+            tracing::warn!("{}", message);
+        }
+    }
+    works
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pandoc from PATH; tests that need a real pandoc are skipped without one
+    fn system_pandoc() -> Option<String> {
+        crate::utils::find_in_path("pandoc").ok()
+    }
+
+    #[test]
+    fn converts_paths_with_spaces_and_non_ascii() {
+        let Some(pandoc) = system_pandoc() else {
+            eprintln!("pandoc not found, skipping");
+            return;
+        };
+
+        let temp = tempfile::tempdir().unwrap();
+        let project = temp.path().join("my docs – Ünïcödé 文档");
+        std::fs::create_dir_all(&project).unwrap();
+        let input = project.join("résumé draft.md");
+        let output = project.join("résumé draft.html");
+        std::fs::write(&input, "# Hello\n").unwrap();
+
+        let args = ["-t".to_string(), "html".to_string()];
+        let status = pandoc_command(&pandoc, &args, &input, &output, &project)
+            .status()
+            .unwrap();
+
+        assert!(status.success());
+        assert!(std::fs::read_to_string(&output).unwrap().contains("Hello"));
+    }
+
+    #[test]
+    fn convert_text_applies_format_extensions() {
+        let Some(pandoc) = system_pandoc() else {
+            eprintln!("pandoc not found, skipping");
+            return;
+        };
+
+        let html = convert_text_with(
+            &pandoc,
+            "first line\nsecond line",
+            "markdown+hard_line_breaks",
+            "html",
+        )
+        .unwrap();
+
+        assert!(html.contains("<br />"), "unexpected output: {}", html);
+    }
 }
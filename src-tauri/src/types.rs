@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PandocInfo {
@@ -9,13 +10,79 @@ pub struct PandocInfo {
     pub supported_output_formats: Vec<String>,
     pub detected_paths: Vec<String>,
     pub search_paths: Vec<String>,
+    pub user_data_dir: Option<String>,
+    pub architecture: Option<String>,
+    /// Features reported by `--version`, e.g. "server" and "lua"
+    pub compiled_features: Vec<String>,
+}
+
+/// Which pandoc installation to use when both managed and system ones exist
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SourcePreference {
+    /// Managed first, then system (the historical behavior)
+    #[default]
+    Auto,
+    PreferManaged,
+    PreferSystem,
+    ManagedOnly,
+    SystemOnly,
+}
+
+impl SourcePreference {
+    pub fn allows_managed(self) -> bool {
+        self != SourcePreference::SystemOnly
+    }
+
+    pub fn allows_system(self) -> bool {
+        self != SourcePreference::ManagedOnly
+    }
+
+    pub fn prefers_system(self) -> bool {
+        matches!(
+            self,
+            SourcePreference::PreferSystem | SourcePreference::SystemOnly
+        )
+    }
+}
+
+/// Proxy settings for release lookups and downloads; unset fields fall back to the
+/// HTTP_PROXY/HTTPS_PROXY/NO_PROXY environment variables
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ProxyConfig {
+    pub http: Option<String>,
+    pub https: Option<String>,
+    /// e.g. "socks5://127.0.0.1:1080"; used for all traffic
+    pub socks: Option<String>,
+    /// Comma-separated hosts that bypass the proxy
+    pub no_proxy: Option<String>,
+}
+
+/// Default conversion options applied to every conversion to one output format
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct FormatProfile {
+    /// Extra pandoc arguments, e.g. ["--standalone", "--toc"]
+    pub extra_args: Vec<String>,
+    pub pdf_engine: Option<String>,
+    pub template: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 pub struct PandocConfig {
     pub custom_path: Option<String>,
     pub use_custom_path: bool,
     pub last_detected_version: String,
+    pub preferred_pdf_engine: Option<String>,
+    pub source_preference: SourcePreference,
+    /// Per-output-format defaults, keyed by format
+    pub format_profiles: HashMap<String, FormatProfile>,
+    pub proxy: ProxyConfig,
+    /// Typst package cache directory used by the typst PDF engine (None for typst's default)
+    pub typst_package_path: Option<String>,
+    /// Probe download mirrors concurrently and download from the fastest one
+    pub race_mirrors: bool,
 }
 
 impl Default for PandocConfig {
@@ -24,6 +91,12 @@ impl Default for PandocConfig {
             custom_path: None,
             use_custom_path: false,
             last_detected_version: String::new(),
+            preferred_pdf_engine: None,
+            source_preference: SourcePreference::Auto,
+            format_profiles: HashMap::new(),
+            proxy: ProxyConfig::default(),
+            typst_package_path: None,
+            race_mirrors: false,
         }
     }
 }
@@ -50,7 +123,67 @@ pub struct DownloadProgress {
     pub downloaded: u64,
     pub total: u64,
     pub percentage: f64,
+    /// Human-readable rate, e.g. "2.5 MB/s"
     pub speed: String,
+    /// Average over the last few seconds
+    pub speed_bytes_per_sec: f64,
+    /// Estimated seconds remaining, None while the speed or total size is unknown
+    pub eta_seconds: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExtractProgress {
+    pub current: u64,
+    pub total: u64,
+    pub percentage: f64,
+    pub unit: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MirrorStatus {
+    pub mirror: String,
+    pub reachable: bool,
+    pub latency_ms: Option<u64>,
+}
+
+/// Installed and latest version of a bundled tool
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ToolVersion {
+    pub current: Option<String>,
+    pub latest: Option<String>,
+    pub update_available: bool,
+}
+
+/// Versions of the app and its bundled tools, for an "About" panel
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AppToolVersions {
+    pub app_version: String,
+    pub pandoc: ToolVersion,
+    pub typst: ToolVersion,
+}
+
+/// Outcome of a release asset download
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DownloadResult {
+    pub path: String,
+    pub bytes: u64,
+    pub duration_ms: u64,
+    /// Mirror the download succeeded through ("GitHub" for a direct download)
+    pub mirror_used: String,
+    /// Number of mirrors tried, including the successful one
+    pub attempts: u32,
+    /// Release tag that was downloaded, when the caller resolved one
+    pub version: Option<String>,
+}
+
+/// Whether a release service can be reached through the configured proxy
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConnectivityStatus {
+    pub service: String,
+    pub url: String,
+    pub reachable: bool,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -60,3 +193,224 @@ pub struct VersionInfo {
     pub available_versions: Vec<String>,
     pub is_update_available: bool,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FormatCapability {
+    pub format: String,
+    pub is_supported: bool,
+    pub requires_engine: bool,
+    pub available_engines: Vec<String>,
+    pub can_convert: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OutputFormatCapability {
+    pub format: String,
+    pub requires_engine: bool,
+    pub engines: Vec<String>,
+    pub available_engines: Vec<String>,
+    pub is_available: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConversionMatrix {
+    pub input_formats: Vec<String>,
+    pub output_formats: Vec<OutputFormatCapability>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConvertAndOpenResult {
+    pub message: String,
+    pub opened_path: Option<String>,
+}
+
+/// Optional settings for `convert_with_pandoc`; anything left out uses pandoc's default
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ConversionOptions {
+    /// None or "auto" lets pandoc detect the input format
+    pub input_format: Option<String>,
+    pub custom_pandoc_path: Option<String>,
+    pub pdf_engine: Option<String>,
+    pub reference_doc: Option<String>,
+    pub bibliography: Option<Vec<String>>,
+    pub csl: Option<String>,
+    /// Stream pandoc's log to the frontend as `conversion-log` events
+    pub verbose: Option<bool>,
+    pub filters: Option<Vec<String>>,
+    pub lua_filters: Option<Vec<String>>,
+    pub data_dir: Option<String>,
+    /// "lf", "crlf" or "native"
+    pub line_ending: Option<String>,
+    pub template: Option<String>,
+    /// Template variables, passed as `-V key=value`
+    pub variables: Option<HashMap<String, String>>,
+    pub extract_media: Option<String>,
+    /// Replace an existing output file (default true)
+    pub overwrite: Option<bool>,
+    pub epub_cover: Option<String>,
+    pub epub_metadata: Option<String>,
+    pub defaults_file: Option<String>,
+    pub working_dir: Option<String>,
+    pub sandbox: Option<bool>,
+    pub use_cache: Option<bool>,
+    pub highlight_style: Option<String>,
+    pub split_level: Option<u32>,
+    pub toc: Option<bool>,
+    pub toc_depth: Option<u32>,
+    pub self_contained: Option<bool>,
+    pub metadata_files: Option<Vec<String>>,
+    /// Extra pandoc arguments, added before the options above so those win
+    pub extra_args: Option<Vec<String>>,
+    /// Id for stopping the conversion through `cancel_conversion`
+    pub conversion_id: Option<String>,
+}
+
+/// One target of `convert_multi_output`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OutputSpec {
+    pub output_file: String,
+    pub output_format: String,
+    /// Conversion options for this output only, e.g. `pdf_engine` or `extra_args`
+    #[serde(flatten)]
+    pub options: ConversionOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConversionResult {
+    pub output_file: String,
+    pub output_format: String,
+    pub success: bool,
+    /// Skipped because an earlier output failed with `stop_on_error`
+    pub cancelled: bool,
+    /// Success message, or the error when the conversion failed
+    pub message: String,
+}
+
+/// Counts over all outputs of a `convert_multi_output` call
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchResult {
+    pub results: Vec<ConversionResult>,
+    pub summary: BatchSummary,
+}
+
+/// Timings of one engine in `benchmark_conversion`, warm-up run excluded
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BenchmarkResult {
+    pub engine: String,
+    /// Number of timed runs
+    pub runs: u32,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub mean_ms: f64,
+    pub output_size: Option<u64>,
+    /// Set when a run failed; timings cover the runs before it
+    pub error: Option<String>,
+}
+
+/// Comparison of the same conversion run with two pandoc executables
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DiffResult {
+    pub identical: bool,
+    /// Binary outputs only report sizes and `identical`
+    pub binary: bool,
+    pub size_a: u64,
+    pub size_b: u64,
+    pub lines_added: u64,
+    pub lines_removed: u64,
+    pub words_added: u64,
+    pub words_removed: u64,
+    /// Unified line diff from output A to output B, None when identical or binary
+    pub diff: Option<String>,
+}
+
+/// Outcome of checking a custom pandoc path
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathStatus {
+    Missing,
+    NotExecutable,
+    /// Blocked by macOS Gatekeeper
+    Quarantined,
+    WrongArchitecture,
+    /// Runs, but isn't pandoc
+    NotPandoc,
+    Valid,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PathDiagnosis {
+    pub status: PathStatus,
+    /// Pandoc version when the path is valid
+    pub version: Option<String>,
+    /// Human-readable explanation of the status
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SearchPathEntry {
+    pub path: String,
+    pub exists: bool,
+    pub is_valid: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DocumentStats {
+    pub word_count: usize,
+    pub reading_time_minutes: u32,
+    pub estimated_pages: u32,
+    pub heading_count: usize,
+    pub image_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HistoryEntry {
+    pub input_file: String,
+    pub output_file: String,
+    pub input_format: Option<String>,
+    pub output_format: String,
+    pub pdf_engine: Option<String>,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub duration_ms: u64,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LatexInfo {
+    pub name: String,
+    pub version: String,
+    pub path: Option<String>,
+}
+
+/// A release archive found in a download directory
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArchiveInfo {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    /// "pandoc" or "typst", detected from the file name
+    pub tool: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Result of checking a managed tool installation
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstallHealth {
+    pub tool: String,
+    pub install_dir: Option<String>,
+    pub executable: Option<String>,
+    pub version: Option<String>,
+    /// Files recorded at install time that are no longer present
+    pub missing_files: Vec<String>,
+    pub problems: Vec<String>,
+    pub healthy: bool,
+}
@@ -1,15 +1,15 @@
 use std::env;
-use std::path::Path;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::Duration;
 
 /// Get OS-specific search paths for Pandoc
 pub fn get_search_paths() -> Vec<String> {
     let mut paths = Vec::new();
 
-    // First try PATH
-    if let Ok(path_pandoc) = get_path_pandoc_internal() {
-        paths.push(path_pandoc);
-    }
+    // First try PATH (every match, in PATH order)
+    paths.extend(get_path_pandoc_internal());
 
     // Platform-specific common paths
     let common_paths = if cfg!(target_os = "windows") {
@@ -108,27 +108,203 @@ fn get_linux_common_paths() -> Vec<String> {
     paths
 }
 
-/// Find pandoc in PATH
-fn get_path_pandoc_internal() -> Result<String, String> {
-    let output = if cfg!(target_os = "windows") {
-        create_hidden_command("where").arg("pandoc").output()
+/// Find every pandoc executable in PATH, without duplicates
+/// Callers validate the candidates, so a broken shim earlier in PATH doesn't hide a later install
+fn get_path_pandoc_internal() -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+    for path in find_all_in_path("pandoc") {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Find a program in PATH
+pub fn find_in_path(program: &str) -> Result<String, String> {
+    find_all_in_path(program)
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Not found in PATH".to_string())
+}
+
+/// Find an installed Chromium-based browser (Chrome, Edge, Chromium) that can print to PDF
+/// headlessly; browsers installed outside PATH are found in their usual locations
+pub fn find_chromium_browser() -> Option<PathBuf> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+
+    if cfg!(target_os = "windows") {
+        for var in ["ProgramFiles", "ProgramFiles(x86)", "LOCALAPPDATA"] {
+            if let Ok(base) = env::var(var) {
+                let base = PathBuf::from(base);
+                candidates.push(base.join(r"Google\Chrome\Application\chrome.exe"));
+                candidates.push(base.join(r"Microsoft\Edge\Application\msedge.exe"));
+                candidates.push(base.join(r"Chromium\Application\chrome.exe"));
+            }
+        }
+    } else if cfg!(target_os = "macos") {
+        for app in [
+            "Google Chrome.app/Contents/MacOS/Google Chrome",
+            "Chromium.app/Contents/MacOS/Chromium",
+            "Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+            "Brave Browser.app/Contents/MacOS/Brave Browser",
+        ] {
+            candidates.push(Path::new("/Applications").join(app));
+        }
     } else {
-        create_hidden_command("which").arg("pandoc").output()
-    };
+        for program in [
+            "google-chrome",
+            "google-chrome-stable",
+            "chromium",
+            "chromium-browser",
+            "microsoft-edge",
+        ] {
+            if let Ok(path) = find_in_path(program) {
+                candidates.push(PathBuf::from(path));
+            }
+        }
+    }
+
+    candidates.into_iter().find(|path| path.is_file())
+}
+
+/// Find every match for a program in PATH, in PATH order
+fn find_all_in_path(program: &str) -> Vec<String> {
+    // `where` prints in the console code page, which mangles non-ASCII paths
+    // (e.g. C:\Users\用户\...), so PATH is searched directly on Windows
+    if cfg!(target_os = "windows") {
+        return search_path_dirs(program);
+    }
+
+    let output = create_hidden_command("which")
+        .arg("-a")
+        .arg(program)
+        .output();
 
     match output {
-        Ok(output) if output.status.success() => {
-            let path = String::from_utf8(output.stdout)
-                .map_err(|_| "Failed to read path output".to_string())?;
-            Ok(path.lines().next().unwrap_or("").trim().to_string())
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Search the PATH directories for a program, trying each PATHEXT extension for bare names
+/// Paths that aren't valid Unicode are skipped rather than returned corrupted
+fn search_path_dirs(program: &str) -> Vec<String> {
+    let Some(path_var) = env::var_os("PATH") else {
+        return Vec::new();
+    };
+
+    let extensions: Vec<String> = if Path::new(program).extension().is_some() {
+        vec![String::new()]
+    } else {
+        env::var("PATHEXT")
+            .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+            .split(';')
+            .filter(|ext| !ext.is_empty())
+            .map(|ext| ext.to_lowercase())
+            .collect()
+    };
+
+    let mut found = Vec::new();
+    for dir in env::split_paths(&path_var) {
+        for ext in &extensions {
+            let candidate = dir.join(format!("{}{}", program, ext));
+            if !candidate.is_file() {
+                continue;
+            }
+            if let Ok(path) = candidate.into_os_string().into_string() {
+                if !found.contains(&path) {
+                    found.push(path);
+                }
+            }
+        }
+    }
+
+    found
+}
+
+/// Resolve a helper program (e.g. a pandoc filter) to a full path
+/// Paths are checked for existence relative to `base_dir`, the directory pandoc runs in;
+/// bare names are looked up there, next to pandoc, in PATH, and in the same directories
+/// searched for pandoc itself
+pub fn resolve_tool_path(name: &str, pandoc_cmd: &str, base_dir: &Path) -> Option<String> {
+    let path = Path::new(name);
+    if path.is_absolute() || path.components().count() > 1 {
+        return base_dir.join(path).is_file().then(|| name.to_string());
+    }
+
+    // Pandoc runs in `base_dir`, so a file there is found under its bare name
+    if base_dir.join(path).is_file() {
+        return Some(name.to_string());
+    }
+
+    let exe_names = if cfg!(target_os = "windows") && path.extension().is_none() {
+        vec![format!("{}.exe", name), name.to_string()]
+    } else {
+        vec![name.to_string()]
+    };
+
+    let find_in_dirs = |dirs: &[PathBuf]| {
+        dirs.iter()
+            .flat_map(|dir| exe_names.iter().map(move |exe| dir.join(exe)))
+            .find(|candidate| candidate.is_file())
+            .map(|candidate| candidate.to_string_lossy().to_string())
+    };
+
+    // 1. Next to the pandoc executable in use
+    let pandoc_dir: Vec<PathBuf> = Path::new(pandoc_cmd)
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .map(|parent| parent.to_path_buf())
+        .into_iter()
+        .collect();
+    if let Some(found) = find_in_dirs(&pandoc_dir) {
+        return Some(found);
+    }
+
+    // 2. PATH
+    if let Ok(found) = find_in_path(name) {
+        return Some(found);
+    }
+
+    // 3. Common pandoc installation directories
+    let mut search_dirs: Vec<PathBuf> = Vec::new();
+    for pandoc_path in get_search_paths() {
+        if let Some(parent) = Path::new(&pandoc_path).parent() {
+            let parent = parent.to_path_buf();
+            if !search_dirs.contains(&parent) {
+                search_dirs.push(parent);
+            }
         }
-        _ => Err("Not found in PATH".to_string()),
     }
+
+    find_in_dirs(&search_dirs)
+}
+
+/// How long to wait for a filesystem check before treating the path as unreachable
+pub const PATH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Check whether a path exists without blocking on an offline network or removed drive
+/// The check runs on a helper thread; if it doesn't answer within `timeout` the path is
+/// reported as missing and the thread is left to finish on its own
+pub fn path_exists_with_timeout(path: &Path, timeout: Duration) -> bool {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let path = path.to_path_buf();
+    std::thread::spawn(move || {
+        let _ = tx.send(path.exists());
+    });
+
+    rx.recv_timeout(timeout).unwrap_or(false)
 }
 
 /// Validate if a path contains a working pandoc executable
-pub fn validate_pandoc_executable(path: &str) -> bool {
-    if !Path::new(path).exists() {
+pub fn validate_pandoc_executable<P: AsRef<Path>>(path: P) -> bool {
+    let path = path.as_ref();
+    if !path.exists() {
         return false;
     }
 
@@ -164,6 +340,380 @@ pub fn get_pandoc_asset_pattern() -> &'static str {
     get_pandoc_asset_patterns()[0]
 }
 
+/// Get the native CPU architecture, correcting for Rosetta 2 translation on macOS
+pub fn get_native_arch() -> String {
+    let arch = env::consts::ARCH;
+
+    // An x86_64 build running under Rosetta 2 still reports x86_64,
+    // but the hardware is Apple Silicon and can run the arm64 binaries natively
+    if cfg!(target_os = "macos") && arch == "x86_64" && is_rosetta_translated() {
+        return "aarch64".to_string();
+    }
+
+    arch.to_string()
+}
+
+/// Error code prefix for an executable built for a different CPU architecture
+pub const ARCH_MISMATCH_ERROR: &str = "arch_mismatch";
+
+/// Check whether a failure to start a program means it was built for another architecture
+fn is_arch_mismatch(error: &std::io::Error) -> bool {
+    // ENOEXEC; EBADARCH ("Bad CPU type in executable") on macOS;
+    // ERROR_BAD_EXE_FORMAT and ERROR_EXE_MACHINE_TYPE_MISMATCH on Windows
+    let codes: &[i32] = if cfg!(target_os = "windows") {
+        &[193, 216]
+    } else if cfg!(target_os = "macos") {
+        &[8, 86]
+    } else {
+        &[8]
+    };
+
+    error
+        .raw_os_error()
+        .is_some_and(|code| codes.contains(&code))
+}
+
+/// Error code prefix for an executable file that lacks the execute permission
+pub const NOT_EXECUTABLE_ERROR: &str = "not_executable";
+
+/// Check whether a file exists but isn't marked executable, e.g. after a raw copy
+/// Always false on Windows, which has no execute bit
+pub fn lacks_exec_permission<P: AsRef<Path>>(path: P) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::metadata(path.as_ref())
+            .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 == 0)
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// Set the execute bits on a file (`chmod +x`); a no-op on Windows
+pub fn make_file_executable<P: AsRef<Path>>(path: P) -> Result<(), String> {
+    let path = path.as_ref();
+    if !path.is_file() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = std::fs::metadata(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+            .permissions()
+            .mode();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode | 0o111))
+            .map_err(|e| format!("Failed to make {} executable: {}", path.display(), e))?;
+    }
+
+    Ok(())
+}
+
+/// Error code prefix for an executable macOS Gatekeeper refuses to run
+pub const QUARANTINED_ERROR: &str = "quarantined";
+
+/// Extended attribute macOS puts on downloaded files
+const QUARANTINE_ATTRIBUTE: &str = "com.apple.quarantine";
+
+/// Check whether a file carries the macOS quarantine attribute (always false elsewhere)
+pub fn is_quarantined<P: AsRef<Path>>(path: P) -> bool {
+    if !cfg!(target_os = "macos") {
+        return false;
+    }
+
+    create_hidden_command("xattr")
+        .args(["-p", QUARANTINE_ATTRIBUTE])
+        .arg(path.as_ref())
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Describe an executable Gatekeeper blocks, None if it isn't quarantined
+pub fn quarantine_error<P: AsRef<Path>>(path: P) -> Option<String> {
+    let path = path.as_ref();
+    is_quarantined(path).then(|| {
+        format!(
+            "{}: macOS blocked '{}' because it was downloaded from the internet",
+            QUARANTINED_ERROR,
+            path.display()
+        )
+    })
+}
+
+/// Remove the macOS quarantine attribute from a file or, recursively, a directory,
+/// and make files executable again
+pub fn clear_quarantine_attr<P: AsRef<Path>>(path: P) -> Result<(), String> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Err(format!("Path not found: {}", path.display()));
+    }
+
+    if cfg!(target_os = "macos") {
+        // xattr fails when the attribute is absent, so the outcome is checked afterwards
+        let _ = create_hidden_command("xattr")
+            .args(["-r", "-d", QUARANTINE_ATTRIBUTE])
+            .arg(path)
+            .output();
+
+        if path.is_file() && is_quarantined(path) {
+            return Err(format!(
+                "Failed to remove the quarantine attribute from {}",
+                path.display()
+            ));
+        }
+    }
+
+    if path.is_file() {
+        make_file_executable(path)?;
+    }
+
+    Ok(())
+}
+
+/// Describe a failure to start pandoc, telling architecture mismatches, missing
+/// execute permissions and Gatekeeper quarantine apart from other errors
+pub fn describe_exec_error(program: &str, error: &std::io::Error) -> String {
+    if let Some(message) = quarantine_error(program) {
+        format!("{} ({})", message, error)
+    } else if lacks_exec_permission(program) {
+        format!(
+            "{}: Pandoc at '{}' is not marked as executable; make it executable (chmod +x) and try again ({})",
+            NOT_EXECUTABLE_ERROR, program, error
+        )
+    } else if is_arch_mismatch(error) {
+        format!(
+            "{}: Pandoc at '{}' was built for a different architecture than this {} {} system ({})",
+            ARCH_MISMATCH_ERROR,
+            program,
+            env::consts::OS,
+            get_native_arch(),
+            error
+        )
+    } else {
+        format!("Failed to execute pandoc at '{}': {}", program, error)
+    }
+}
+
+/// Check if the current process is translated by Rosetta 2 (macOS only)
+fn is_rosetta_translated() -> bool {
+    // The sysctl key only exists on Apple Silicon; Intel Macs report an error
+    create_hidden_command("sysctl")
+        .args(["-n", "sysctl.proc_translated"])
+        .output()
+        .map(|output| {
+            output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "1"
+        })
+        .unwrap_or(false)
+}
+
+/// Parse a clean version number from raw version text
+/// Examples:
+/// "pandoc.exe 3.7.0.2" -> "3.7.0.2"
+/// "pandoc 3.7.0.2\nFeatures: +server +lua" -> "3.7.0.2"
+/// "v3.7.0.2" -> "3.7.0.2"
+/// "typst 0.13.1 (8ace67d9)" -> "0.13.1"
+pub fn parse_version(raw: &str) -> String {
+    // Only the first non-empty line of `--version` output carries the version
+    let first_line = raw
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("");
+
+    for part in first_line.split_whitespace() {
+        // Skip program names
+        if part.contains("pandoc") {
+            continue;
+        }
+
+        let part = strip_version_prefix(part);
+
+        // Extract version pattern (digits and dots)
+        if part.starts_with(|c: char| c.is_ascii_digit()) {
+            let version: String = part
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || *c == '.')
+                .collect();
+            let version = version.trim_end_matches('.');
+
+            if !version.is_empty() {
+                return version.to_string();
+            }
+        }
+    }
+
+    // Fallback: clean up the entire line
+    let without_name = first_line.replace("pandoc.exe", "").replace("pandoc", "");
+    let cleaned = strip_version_prefix(without_name.trim()).trim();
+
+    if cleaned.is_empty() {
+        "Unknown".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// Compare two version strings component by component
+/// Missing components count as zero, so "0.13" == "0.13.0"
+/// Returns None if either version is not purely numeric (e.g. "unknown")
+pub fn compare_versions(a: &str, b: &str) -> Option<std::cmp::Ordering> {
+    let parse = |version: &str| -> Option<Vec<u64>> {
+        parse_version(version)
+            .split('.')
+            .map(|part| part.parse::<u64>().ok())
+            .collect()
+    };
+
+    let a = parse(a)?;
+    let b = parse(b)?;
+
+    for i in 0..a.len().max(b.len()) {
+        let left = a.get(i).copied().unwrap_or(0);
+        let right = b.get(i).copied().unwrap_or(0);
+        if left != right {
+            return Some(left.cmp(&right));
+        }
+    }
+
+    Some(std::cmp::Ordering::Equal)
+}
+
+/// Parse the "User data directory: ..." line from `pandoc --version` output
+pub fn parse_user_data_dir(version_text: &str) -> Option<String> {
+    version_text
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("User data directory:"))
+        .map(|dir| dir.trim().to_string())
+        .filter(|dir| !dir.is_empty())
+}
+
+/// Parse the compiled-in features from pandoc's `--version` output
+/// ("Features: +server +lua", "Scripting engine: Lua 5.4")
+/// None if the output lists neither, as with older pandoc versions
+pub fn parse_compiled_features(version_text: &str) -> Option<Vec<String>> {
+    let mut features = Vec::new();
+    let mut reported = false;
+
+    for line in version_text.lines().map(str::trim) {
+        if let Some(list) = line.strip_prefix("Features:") {
+            reported = true;
+            features.extend(
+                list.split_whitespace()
+                    .filter_map(|feature| feature.strip_prefix('+'))
+                    .map(str::to_lowercase),
+            );
+        } else if let Some(engine) = line.strip_prefix("Scripting engine:") {
+            reported = true;
+            if engine.trim().to_lowercase().starts_with("lua") {
+                features.push("lua".to_string());
+            }
+        }
+    }
+
+    features.sort();
+    features.dedup();
+    reported.then_some(features)
+}
+
+/// Detect the architecture of a pandoc executable
+/// Uses arch hints in the `--version` text, falling back to the binary header
+pub fn detect_architecture(path: &str, version_text: &str) -> Option<String> {
+    parse_architecture_hint(version_text).or_else(|| detect_binary_architecture(path))
+}
+
+/// Look for a known architecture name in version output
+fn parse_architecture_hint(version_text: &str) -> Option<String> {
+    const HINTS: &[(&str, &str)] = &[
+        ("x86_64", "x86_64"),
+        ("amd64", "x86_64"),
+        ("aarch64", "aarch64"),
+        ("arm64", "aarch64"),
+        ("i386", "x86"),
+        ("i686", "x86"),
+    ];
+
+    let text = version_text.to_lowercase();
+    HINTS
+        .iter()
+        .find(|(hint, _)| text.contains(hint))
+        .map(|(_, arch)| arch.to_string())
+}
+
+/// Infer the architecture from the executable header (ELF, PE or Mach-O)
+fn detect_binary_architecture(path: &str) -> Option<String> {
+    use std::io::Read;
+
+    let mut header = Vec::with_capacity(4096);
+    std::fs::File::open(path)
+        .ok()?
+        .take(4096)
+        .read_to_end(&mut header)
+        .ok()?;
+
+    let u16_le = |offset: usize| -> Option<u16> {
+        Some(u16::from_le_bytes(
+            header.get(offset..offset + 2)?.try_into().ok()?,
+        ))
+    };
+    let u32_le = |offset: usize| -> Option<u32> {
+        Some(u32::from_le_bytes(
+            header.get(offset..offset + 4)?.try_into().ok()?,
+        ))
+    };
+
+    let arch = if header.starts_with(b"\x7fELF") {
+        // e_machine, assuming little-endian (all supported targets)
+        match u16_le(18)? {
+            0x3E => "x86_64",
+            0xB7 => "aarch64",
+            0x03 => "x86",
+            0x28 => "arm",
+            _ => return None,
+        }
+    } else if header.starts_with(b"MZ") {
+        // PE header offset is stored at 0x3C, machine type follows the "PE\0\0" signature
+        let pe_offset = u32_le(0x3C)? as usize;
+        if header.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+            return None;
+        }
+        match u16_le(pe_offset + 4)? {
+            0x8664 => "x86_64",
+            0xAA64 => "aarch64",
+            0x014C => "x86",
+            _ => return None,
+        }
+    } else if header.starts_with(&[0xCA, 0xFE, 0xBA, 0xBE]) {
+        "universal"
+    } else if header.starts_with(&[0xCF, 0xFA, 0xED, 0xFE])
+        || header.starts_with(&[0xCE, 0xFA, 0xED, 0xFE])
+    {
+        // Mach-O cputype
+        match u32_le(4)? {
+            0x0100_0007 => "x86_64",
+            0x0100_000C => "aarch64",
+            0x0000_0007 => "x86",
+            _ => return None,
+        }
+    } else {
+        return None;
+    };
+
+    Some(arch.to_string())
+}
+
+/// Strip a single leading 'v'/'V' from a version tag (Typst tags look like "v0.13.1")
+pub fn strip_version_prefix(version: &str) -> &str {
+    version.strip_prefix(['v', 'V']).unwrap_or(version)
+}
+
 /// Format file size in human readable format
 pub fn format_file_size(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
@@ -178,8 +728,33 @@ pub fn format_file_size(bytes: u64) -> String {
     format!("{:.1} {}", size, UNITS[unit_index])
 }
 
+/// Check that an output file can be written, without modifying it
+/// Existing files are opened for writing; otherwise a probe file is created next to it
+pub fn check_output_writable(output_path: &Path) -> Result<(), String> {
+    let result = if output_path.exists() {
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(output_path)
+            .map(|_| ())
+    } else {
+        let dir = match output_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let probe = dir.join(format!(".pandoc-write-test-{}", std::process::id()));
+        let created = std::fs::File::create(&probe).map(|_| ());
+        let _ = std::fs::remove_file(&probe);
+        created
+    };
+
+    result.map_err(|e| format!("Cannot write to output location: {}", e))
+}
+
 /// Create a hidden command to avoid PowerShell popup on Windows
-pub fn create_hidden_command(program: &str) -> Command {
+/// On Unix the child leads its own process group, so cancelling a conversion can
+/// signal the whole group and also stop helpers spawned by pandoc (PDF engines, filters)
+/// Takes any OS string so paths can be passed without a lossy UTF-8 round trip
+pub fn create_hidden_command<S: AsRef<OsStr>>(program: S) -> Command {
     #[cfg(target_os = "windows")]
     {
         let mut cmd = Command::new(program);
@@ -188,9 +763,54 @@ pub fn create_hidden_command(program: &str) -> Command {
         cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
         cmd
     }
-    
-    #[cfg(not(target_os = "windows"))]
+
+    #[cfg(unix)]
+    {
+        let mut cmd = Command::new(program);
+        use std::os::unix::process::CommandExt;
+        // Equivalent to setpgid(0, 0) in the child: a new group with the child's pid as id
+        cmd.process_group(0);
+        cmd
+    }
+
+    #[cfg(not(any(target_os = "windows", unix)))]
     {
         Command::new(program)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn compare_versions_ignores_v_prefix() {
+        assert_eq!(compare_versions("v0.13.1", "0.13.1"), Some(Ordering::Equal));
+        assert_eq!(strip_version_prefix("v0.13.1"), "0.13.1");
+    }
+
+    #[test]
+    fn compare_versions_orders_prefixed_tags() {
+        assert_eq!(compare_versions("v0.12.0", "v0.13.0"), Some(Ordering::Less));
+    }
+
+    #[test]
+    fn parse_version_skips_program_name() {
+        assert_eq!(parse_version("pandoc.exe 3.7.0.2"), "3.7.0.2");
+    }
+
+    #[test]
+    fn parse_version_strips_v_prefix() {
+        assert_eq!(parse_version("v3.7.0.2"), "3.7.0.2");
+    }
+
+    #[test]
+    fn parse_version_reads_first_line_of_version_output() {
+        let output = "pandoc 3.7.0.2\n\
+                      Features: +server +lua\n\
+                      Scripting engine: Lua 5.4\n\
+                      User data directory: /home/user/.local/share/pandoc\n";
+        assert_eq!(parse_version(output), "3.7.0.2");
+    }
+}
@@ -0,0 +1,153 @@
+use crate::types::ConversionOptions;
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::mpsc;
+
+/// Quiet period after the last change before converting, so a burst of saves converts once
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Active file watchers keyed by watch id; dropping a watcher stops its conversion loop
+#[derive(Default)]
+pub struct WatchRegistry {
+    watchers: Mutex<HashMap<String, notify::RecommendedWatcher>>,
+    next_id: AtomicU64,
+}
+
+/// Payload of the `watch-converted` event
+#[derive(Serialize, Clone)]
+struct WatchConverted {
+    watch_id: String,
+    success: bool,
+    message: String,
+}
+
+/// Convert a file now and again every time it changes, until `stop_watch` is called
+/// Returns the watch id; each conversion emits a `watch-converted` event
+#[tauri::command]
+pub async fn watch_convert(
+    input_file: String,
+    output_file: String,
+    input_format: Option<String>,
+    output_format: String,
+    custom_pandoc_path: Option<String>,
+    pdf_engine: Option<String>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let input_path = PathBuf::from(&input_file);
+    if !input_path.is_file() {
+        return Err(format!("Input file not found: {}", input_file));
+    }
+
+    // Watch the parent directory: editors often save by replacing the file,
+    // which would silently end a watch on the file itself
+    let watch_dir = input_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+        .to_path_buf();
+    let file_name = input_path.file_name().map(|n| n.to_os_string());
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            let touches_input = event
+                .paths
+                .iter()
+                .any(|path| path.file_name().map(|n| n.to_os_string()) == file_name);
+
+            if touches_input && (event.kind.is_modify() || event.kind.is_create()) {
+                let _ = tx.send(());
+            }
+        }
+    })
+    .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+
+    watcher
+        .watch(&watch_dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch {}: {}", watch_dir.display(), e))?;
+
+    let registry = app_handle.state::<WatchRegistry>();
+    let watch_id = format!("watch-{}", registry.next_id.fetch_add(1, Ordering::Relaxed));
+    registry
+        .watchers
+        .lock()
+        .map_err(|_| "Watch registry is unavailable".to_string())?
+        .insert(watch_id.clone(), watcher);
+
+    let loop_id = watch_id.clone();
+    tauri::async_runtime::spawn(async move {
+        // Convert once up front so the output exists right away
+        let mut pending = true;
+
+        loop {
+            if !pending {
+                // The channel closes when stop_watch drops the watcher
+                if rx.recv().await.is_none() {
+                    break;
+                }
+            }
+
+            // Debounce: wait until the file has been quiet for a moment
+            while let Ok(Some(())) = tokio::time::timeout(WATCH_DEBOUNCE, rx.recv()).await {}
+            pending = false;
+
+            let options = ConversionOptions {
+                input_format: input_format.clone(),
+                custom_pandoc_path: custom_pandoc_path.clone(),
+                pdf_engine: pdf_engine.clone(),
+                ..Default::default()
+            };
+            let result = crate::pandoc::convert_with_pandoc(
+                input_file.clone(),
+                output_file.clone(),
+                output_format.clone(),
+                options,
+                app_handle.clone(),
+            )
+            .await;
+
+            let payload = WatchConverted {
+                watch_id: loop_id.clone(),
+                success: result.is_ok(),
+                message: result.unwrap_or_else(|e| e),
+            };
+            let _ = app_handle.emit("watch-converted", payload);
+        }
+    });
+
+    Ok(watch_id)
+}
+
+/// Stop watching a file started with `watch_convert`
+#[tauri::command]
+pub fn stop_watch(watch_id: String, app_handle: AppHandle) -> Result<(), String> {
+    let registry = app_handle.state::<WatchRegistry>();
+    let watcher = registry
+        .watchers
+        .lock()
+        .map_err(|_| "Watch registry is unavailable".to_string())?
+        .remove(&watch_id);
+
+    match watcher {
+        Some(_) => Ok(()),
+        None => Err(format!("No active watch with id '{}'", watch_id)),
+    }
+}
+
+/// Stop every active watch, returning how many were stopped
+pub fn stop_all_watches(app_handle: &AppHandle) -> usize {
+    let Some(registry) = app_handle.try_state::<WatchRegistry>() else {
+        return 0;
+    };
+
+    match registry.watchers.lock() {
+        Ok(mut watchers) => watchers.drain().count(),
+        Err(_) => 0,
+    }
+}
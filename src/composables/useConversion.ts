@@ -5,6 +5,7 @@ import { usePandocManager } from "./usePandocManager";
 import { useMessages } from "./useMessages";
 import { useNotification } from "./useNotification";
 import { generateOutputFilenameWithExt } from "./useUtils";
+import type { ConversionOptions } from "../types/pandoc";
 
 const isConverting = ref<boolean>(false);
 const progress = ref<number>(0);
@@ -79,14 +80,16 @@ export function useConversion() {
       displayMessage("Starting conversion...", "info");
       notifyConversionStarted(fileName);
 
+      const options: ConversionOptions = {
+        custom_pandoc_path: pandocInfo.value?.path || null,
+        pdf_engine:
+          outputFormat.value === "pdf" ? selectedPdfEngine.value : null,
+      };
       const result = await invoke<string>("convert_with_pandoc", {
         inputFile: inputFile.value,
         outputFile: outputPath,
-        inputFormat: null,
         outputFormat: outputFormat.value,
-        customPandocPath: pandocInfo.value?.path || null,
-        pdfEngine:
-          outputFormat.value === "pdf" ? selectedPdfEngine.value : null,
+        options,
       });
 
       progress.value = 100;
@@ -1,7 +1,11 @@
 import { ref, computed, readonly } from "vue";
 import { invoke } from "@tauri-apps/api/core";
 import { useMessages } from "./useMessages";
-import type { PandocSource, PandocManager } from "../types/pandoc";
+import type {
+  DownloadResult,
+  PandocSource,
+  PandocManager,
+} from "../types/pandoc";
 
 const availableSources = ref<PandocManager[]>([]);
 const currentManager = ref<PandocManager | null>(null);
@@ -117,11 +121,14 @@ export function usePandocManager() {
   ): Promise<boolean> => {
     try {
       isLoading.value = true;
-      const result = await invoke<string>("download_typst", {
+      const result = await invoke<DownloadResult>("download_typst", {
         version: version || null,
         downloadDir: downloadDir || "downloads",
       });
-      displayMessage(`Typst downloaded successfully: ${result}`, "success");
+      displayMessage(
+        `Typst${result.version ? ` ${result.version}` : ""} downloaded successfully: ${result.path} (${(result.duration_ms / 1000).toFixed(1)}s via ${result.mirror_used})`,
+        "success",
+      );
       return true;
     } catch (error) {
       displayMessage(`Failed to download Typst: ${error}`, "error");
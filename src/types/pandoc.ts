@@ -7,6 +7,15 @@ export interface PandocInfo {
   supported_output_formats: string[];
   detected_paths: string[];
   search_paths: string[];
+  user_data_dir: string | null;
+  architecture: string | null;
+  compiled_features: string[];
+}
+
+export interface SearchPathEntry {
+  path: string;
+  exists: boolean;
+  is_valid: boolean;
 }
 
 // Pandoc source types for new manager system
@@ -23,6 +32,14 @@ export interface PandocManager {
   available: boolean;
 }
 
+export interface SourceVersion {
+  source: PandocSource;
+  path: string | null;
+  version: string | null;
+  is_active: boolean;
+  is_outdated: boolean;
+}
+
 // Version management types
 export interface PandocRelease {
   tag_name: string;
@@ -37,6 +54,33 @@ export interface PandocRelease {
   }>;
 }
 
+export interface MirrorStatus {
+  mirror: string;
+  reachable: boolean;
+  latency_ms: number | null;
+}
+
+export interface ToolVersion {
+  current: string | null;
+  latest: string | null;
+  update_available: boolean;
+}
+
+export interface AppToolVersions {
+  app_version: string;
+  pandoc: ToolVersion;
+  typst: ToolVersion;
+}
+
+export interface DownloadResult {
+  path: string;
+  bytes: number;
+  duration_ms: number;
+  mirror_used: string;
+  attempts: number;
+  version: string | null;
+}
+
 export interface VersionInfo {
   current: string | null;
   latest: string | null;
@@ -49,6 +93,185 @@ export interface DownloadProgress {
   total: number;
   percentage: number;
   speed: string;
+  speed_bytes_per_sec: number;
+  eta_seconds: number | null;
+}
+
+export interface ExtractProgress {
+  current: number;
+  total: number;
+  percentage: number;
+  unit: "files" | "bytes";
+}
+
+export interface ConvertAndOpenResult {
+  message: string;
+  opened_path: string | null;
+}
+
+export interface ConversionOptions {
+  input_format?: string | null;
+  custom_pandoc_path?: string | null;
+  pdf_engine?: string | null;
+  reference_doc?: string | null;
+  bibliography?: string[] | null;
+  csl?: string | null;
+  verbose?: boolean | null;
+  filters?: string[] | null;
+  lua_filters?: string[] | null;
+  data_dir?: string | null;
+  line_ending?: string | null;
+  template?: string | null;
+  variables?: Record<string, string> | null;
+  extract_media?: string | null;
+  overwrite?: boolean | null;
+  epub_cover?: string | null;
+  epub_metadata?: string | null;
+  defaults_file?: string | null;
+  working_dir?: string | null;
+  sandbox?: boolean | null;
+  use_cache?: boolean | null;
+  highlight_style?: string | null;
+  split_level?: number | null;
+  toc?: boolean | null;
+  toc_depth?: number | null;
+  self_contained?: boolean | null;
+  metadata_files?: string[] | null;
+  extra_args?: string[] | null;
+  conversion_id?: string | null;
+}
+
+export interface OutputSpec extends ConversionOptions {
+  output_file: string;
+  output_format: string;
+}
+
+export interface ConversionResult {
+  output_file: string;
+  output_format: string;
+  success: boolean;
+  cancelled: boolean;
+  message: string;
+}
+
+export interface BatchSummary {
+  total: number;
+  succeeded: number;
+  failed: number;
+  cancelled: number;
+  elapsed_ms: number;
+}
+
+export interface BatchResult {
+  results: ConversionResult[];
+  summary: BatchSummary;
+}
+
+export interface BenchmarkResult {
+  engine: string;
+  runs: number;
+  min_ms: number;
+  median_ms: number;
+  mean_ms: number;
+  output_size: number | null;
+  error: string | null;
+}
+
+export interface DiffResult {
+  identical: boolean;
+  binary: boolean;
+  size_a: number;
+  size_b: number;
+  lines_added: number;
+  lines_removed: number;
+  words_added: number;
+  words_removed: number;
+  diff: string | null;
+}
+
+export type PathStatus =
+  | "Missing"
+  | "NotExecutable"
+  | "Quarantined"
+  | "WrongArchitecture"
+  | "NotPandoc"
+  | "Valid";
+
+export interface PathDiagnosis {
+  status: PathStatus;
+  version: string | null;
+  message: string;
+}
+
+export interface DocumentStats {
+  word_count: number;
+  reading_time_minutes: number;
+  estimated_pages: number;
+  heading_count: number;
+  image_count: number;
+}
+
+export interface HistoryEntry {
+  input_file: string;
+  output_file: string;
+  input_format: string | null;
+  output_format: string;
+  pdf_engine: string | null;
+  timestamp: number;
+  duration_ms: number;
+  success: boolean;
+  error: string | null;
+}
+
+export interface LatexInfo {
+  name: string;
+  version: string;
+  path: string | null;
+}
+
+export interface ArchiveInfo {
+  name: string;
+  path: string;
+  size: number;
+  tool: string | null;
+  version: string | null;
+}
+
+export interface InstallHealth {
+  tool: string;
+  install_dir: string | null;
+  executable: string | null;
+  version: string | null;
+  missing_files: string[];
+  problems: string[];
+  healthy: boolean;
+}
+
+export interface WatchConverted {
+  watch_id: string;
+  success: boolean;
+  message: string;
+}
+
+export interface ProxyConfig {
+  http?: string;
+  https?: string;
+  socks?: string;
+  no_proxy?: string;
+}
+
+export interface ConnectivityStatus {
+  service: string;
+  url: string;
+  reachable: boolean;
+  latency_ms: number | null;
+  error: string | null;
+}
+
+export interface FormatProfile {
+  extra_args: string[];
+  pdf_engine?: string;
+  template?: string;
 }
 
 // Pandoc configuration
@@ -56,8 +279,21 @@ export interface PandocConfig {
   custom_path?: string;
   use_custom_path: boolean;
   last_detected_version: string;
+  preferred_pdf_engine?: string;
+  source_preference?: SourcePreference;
+  format_profiles?: Record<string, FormatProfile>;
+  proxy?: ProxyConfig;
+  typst_package_path?: string;
+  race_mirrors?: boolean;
 }
 
+export type SourcePreference =
+  | "Auto"
+  | "PreferManaged"
+  | "PreferSystem"
+  | "ManagedOnly"
+  | "SystemOnly";
+
 // Format mapping for file extensions to pandoc input formats
 export const INPUT_FORMAT_MAP: Record<string, string> = {
   // Markdown formats
@@ -572,6 +808,10 @@ export enum PandocError {
   FORMAT_NOT_SUPPORTED = "format_not_supported",
   FILE_NOT_FOUND = "file_not_found",
   PERMISSION_DENIED = "permission_denied",
+  OUTPUT_EXISTS = "output_exists",
+  ARCH_MISMATCH = "arch_mismatch",
+  QUARANTINED = "quarantined",
+  NOT_EXECUTABLE = "not_executable",
 }
 
 export interface ErrorInfo {
@@ -621,6 +861,26 @@ export const ERROR_MESSAGES: Record<PandocError, ErrorInfo> = {
     message: "No permission to read input file or write output file",
     actions: ["Check File Permissions", "Choose Different Location"],
   },
+  [PandocError.OUTPUT_EXISTS]: {
+    title: "Output File Exists",
+    message: "The output file already exists and overwriting is disabled",
+    actions: ["Overwrite File", "Choose Different Location"],
+  },
+  [PandocError.ARCH_MISMATCH]: {
+    title: "Wrong Architecture",
+    message: "Pandoc was built for a different processor architecture",
+    actions: ["Reinstall for This Machine", "Choose Different Pandoc"],
+  },
+  [PandocError.QUARANTINED]: {
+    title: "Blocked by macOS",
+    message: "macOS Gatekeeper blocked this downloaded executable",
+    actions: ["Allow Executable", "Choose Different Pandoc"],
+  },
+  [PandocError.NOT_EXECUTABLE]: {
+    title: "Not Executable",
+    message: "The pandoc file exists but is not marked as executable",
+    actions: ["Make Executable", "Choose Different Pandoc"],
+  },
 };
 
 // Note: Utility functions have been moved to src/composables/useUtils.ts